@@ -1,95 +1,549 @@
-use serde::Serialize;
+use std::time::Duration;
 
-use crate::{common::Int, mi_entities::Note};
+use reqwest::{header::HeaderMap, Url};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    common::Int,
+    drive_upload::DriveUpload,
+    endpoints::{
+        antennas::AntennasNotesRequest,
+        channels::ChannelsTimelineRequest,
+        drive::{
+            DriveFilesFindByHashRequest, DriveFilesRequest, DriveFilesUpdateRequest,
+            DriveFoldersCreateRequest, DriveFoldersRequest, DriveFoldersUpdateRequest,
+            FileIdRequest, FolderIdRequest,
+        },
+        notes::{
+            NoteIdRequest, NotesConversationRequest, NotesPollsVoteRequest,
+            NotesReactionsCreateRequest, NotesReactionsRequest, NotesSearchByTagRequest,
+            NotesUpdateRequest, NotesUserListTimelineRequest,
+        },
+        roles::RolesNotesRequest,
+        users::UsersNotesRequest,
+        AntennasNotes, ChannelsTimeline, DriveFiles, DriveFilesDelete, DriveFilesFindByHash,
+        DriveFilesShow, DriveFilesUpdate, DriveFolders, DriveFoldersCreate, DriveFoldersDelete,
+        DriveFoldersUpdate, Endpoint, NotesConversation, NotesCreate, NotesDelete,
+        NotesGlobalTimeline, NotesHybridTimeline, NotesLocalTimeline, NotesPollsVote,
+        NotesReactions, NotesReactionsCreate, NotesReactionsDelete, NotesSearchByTag, NotesShow,
+        NotesTimeline, NotesUnrenote, NotesUpdate, NotesUserListTimeline, RolesNotes,
+        TimelineOptions, UsersNotes,
+    },
+    error::{Error, Result},
+    mi_entities::{drive_file::DriveFolder, note::NoteReaction, DriveFile, Note},
+    note_draft::NoteDraft,
+    paginator::{PaginatedEndpoint, Pagination, Paginator},
+    reaction::Reaction,
+};
 
 pub struct Client {
-    host: String,
+    http: reqwest::Client,
+    base_url: Url,
     token: Option<String>,
 }
 
 impl Client {
-    pub fn new(host: impl Into<String>, token: Option<impl Into<String>>) -> Self {
-        Self {
-            host: host.into(),
-            token: token.map(|x| x.into()),
+    /// Connects to `https://{host}/`.
+    ///
+    /// Fails with [`Error::InvalidUrl`] if `host` doesn't form a valid URL.
+    pub fn new(host: impl Into<String>, token: Option<impl Into<String>>) -> Result<Self> {
+        let mut builder = Self::builder(host);
+        if let Some(token) = token {
+            builder = builder.token(token);
         }
+        builder.build()
     }
 
-    pub fn create_note(&self, text: String) {
-        todo!()
+    pub fn builder(host: impl Into<String>) -> ClientBuilder {
+        ClientBuilder::new(host)
     }
 
-    pub async fn create_reaction(&self, node_id: &str, reaction: &str) -> anyhow::Result<()> {
-        let res = reqwest::Client::new()
-            .post(format!("https://{}/api/notes/reactions/create", &self.host))
-            .json(&serde_json::json!({"noteId": node_id,"reaction": reaction,"i": self.token.as_ref().unwrap()}))
-            .send()
-            .await?;
-        dbg!("{res:#?}");
-        res.error_for_status()?;
-        Ok(())
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
     }
 
-    pub async fn conversation(&self, note_id: &str) -> anyhow::Result<Vec<Note>> {
-        #[derive(Debug, Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct ReqBody<'a> {
-            note_id: &'a str,
+    pub fn http_client(&self) -> &reqwest::Client {
+        &self.http
+    }
 
-            #[serde(default, skip_serializing_if = "Option::is_none")]
-            limit: Option<Int>,
+    /// The streaming API URL, with `ws`/`wss` matching the base URL's scheme.
+    pub fn streaming_url(&self) -> Result<Url> {
+        let mut url = self.url("stream")?;
+        let scheme = match url.scheme() {
+            "http" => "ws",
+            _ => "wss",
+        };
+        url.set_scheme(scheme)
+            .map_err(|_| Error::InvalidUrl(url.to_string()))?;
+        if let Some(token) = &self.token {
+            url.query_pairs_mut().append_pair("i", token);
+        }
+        Ok(url)
+    }
 
-            #[serde(default, skip_serializing_if = "Option::is_none")]
-            offset: Option<Int>,
+    fn url(&self, path: &str) -> Result<Url> {
+        self.base_url
+            .join(path)
+            .map_err(|_| Error::InvalidUrl(format!("{}{path}", self.base_url)))
+    }
+
+    /// Calls `E` with the token injected as `i`.
+    pub async fn request<E: Endpoint>(&self, req: &E::Request) -> Result<E::Response> {
+        #[derive(Debug, Serialize)]
+        struct ReqBody<'a, T> {
+            #[serde(flatten)]
+            req: &'a T,
 
             #[serde(default, skip_serializing_if = "Option::is_none")]
             i: Option<&'a str>,
         }
-        let c = reqwest::Client::new();
-        let notes: Vec<Note> = c
-            .post(format!("https://{}/api/notes/conversation", &self.host))
+        if E::REQUIRES_AUTH && self.token.is_none() {
+            return Err(Error::MissingToken);
+        }
+        let res = self
+            .http
+            .post(self.url(&format!("api/{}", E::PATH))?)
             .json(&ReqBody {
-                note_id,
-                limit: None,
-                offset: None,
+                req,
                 i: self.token.as_deref(),
             })
             .send()
-            .await?
-            .error_for_status()?
-            .json()
             .await?;
 
-        Ok(notes)
+        decode(res).await
     }
 
-    pub async fn read_ltl(&self) -> anyhow::Result<Vec<Note>> {
-        #[derive(Debug, Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct ReqBody<'a> {
-            #[serde(default, skip_serializing_if = "Option::is_none")]
-            limit: Option<Int>,
+    /// Uploads to `drive/files/create` as multipart.
+    pub async fn upload_file(&self, upload: DriveUpload) -> Result<DriveFile> {
+        let Some(token) = &self.token else {
+            return Err(Error::MissingToken);
+        };
+        let res = self
+            .http
+            .post(self.url("api/drive/files/create")?)
+            .multipart(upload.into_form(token))
+            .send()
+            .await?;
 
-            #[serde(default, skip_serializing_if = "Option::is_none")]
-            offset: Option<Int>,
+        decode(res).await
+    }
 
-            #[serde(default, skip_serializing_if = "Option::is_none")]
-            i: Option<&'a str>,
-        }
-        let c = reqwest::Client::new();
-        let notes: Vec<Note> = c
-            .post(format!("https://{}/api/notes/local-timeline", &self.host))
-            .json(&ReqBody {
-                limit: None,
-                offset: Some(100),
-                i: self.token.as_deref(),
+    /// Lists files in `folder_id`, or in the root folder if `None`.
+    pub async fn drive_files(
+        &self,
+        folder_id: Option<&str>,
+        pagination: Pagination,
+    ) -> Result<Vec<DriveFile>> {
+        self.request::<DriveFiles>(&DriveFilesRequest {
+            folder_id: folder_id.map(str::to_owned),
+            r#type: None,
+            pagination,
+        })
+        .await
+    }
+
+    /// Lists folders in `folder_id`, or in the root folder if `None`.
+    pub async fn drive_folders(
+        &self,
+        folder_id: Option<&str>,
+        pagination: Pagination,
+    ) -> Result<Vec<DriveFolder>> {
+        self.request::<DriveFolders>(&DriveFoldersRequest {
+            folder_id: folder_id.map(str::to_owned),
+            pagination,
+        })
+        .await
+    }
+
+    pub async fn show_file(&self, file_id: &str) -> Result<DriveFile> {
+        self.request::<DriveFilesShow>(&FileIdRequest {
+            file_id: file_id.to_owned(),
+        })
+        .await
+    }
+
+    pub async fn update_file(&self, req: &DriveFilesUpdateRequest) -> Result<DriveFile> {
+        self.request::<DriveFilesUpdate>(req).await
+    }
+
+    pub async fn delete_file(&self, file_id: &str) -> Result<()> {
+        self.request::<DriveFilesDelete>(&FileIdRequest {
+            file_id: file_id.to_owned(),
+        })
+        .await
+    }
+
+    /// Finds the user's files whose MD5 is `md5`, in lowercase hex.
+    pub async fn find_files_by_hash(&self, md5: &str) -> Result<Vec<DriveFile>> {
+        self.request::<DriveFilesFindByHash>(&DriveFilesFindByHashRequest {
+            md5: md5.to_owned(),
+        })
+        .await
+    }
+
+    pub async fn create_folder(&self, name: &str, parent_id: Option<&str>) -> Result<DriveFolder> {
+        self.request::<DriveFoldersCreate>(&DriveFoldersCreateRequest {
+            name: name.to_owned(),
+            parent_id: parent_id.map(str::to_owned),
+        })
+        .await
+    }
+
+    pub async fn rename_folder(&self, folder_id: &str, name: &str) -> Result<DriveFolder> {
+        self.request::<DriveFoldersUpdate>(&DriveFoldersUpdateRequest {
+            folder_id: folder_id.to_owned(),
+            name: Some(name.to_owned()),
+            parent_id: None,
+        })
+        .await
+    }
+
+    /// Moves the folder into `parent_id`, or to the root folder if `None`.
+    pub async fn move_folder(
+        &self,
+        folder_id: &str,
+        parent_id: Option<&str>,
+    ) -> Result<DriveFolder> {
+        self.request::<DriveFoldersUpdate>(&DriveFoldersUpdateRequest {
+            folder_id: folder_id.to_owned(),
+            name: None,
+            parent_id: Some(parent_id.map(str::to_owned)),
+        })
+        .await
+    }
+
+    /// Fails with `HAS_CHILD_FILES_OR_FOLDERS` unless the folder is empty.
+    pub async fn delete_folder(&self, folder_id: &str) -> Result<()> {
+        self.request::<DriveFoldersDelete>(&FolderIdRequest {
+            folder_id: folder_id.to_owned(),
+        })
+        .await
+    }
+
+    /// Walks `E` from the bounds set in `req`. See [`Paginator`].
+    pub fn paginate<'a, E: PaginatedEndpoint + 'a>(&'a self, req: E::Request) -> Paginator<'a, E> {
+        Paginator::new(self, req)
+    }
+
+    pub async fn create_note(&self, draft: &NoteDraft) -> Result<Note> {
+        let res = self.request::<NotesCreate>(draft).await?;
+        Ok(res.created_note)
+    }
+
+    /// Fails with [`Error::NoteDeleted`] or [`Error::NoteHidden`] instead of returning a
+    /// note whose content isn't available.
+    pub async fn show_note(&self, note_id: &str) -> Result<Note> {
+        let note = self
+            .request::<NotesShow>(&NoteIdRequest {
+                note_id: note_id.to_owned(),
             })
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
             .await?;
 
-        Ok(notes)
+        Error::check_note(note)
+    }
+
+    pub async fn delete_note(&self, note_id: &str) -> Result<()> {
+        self.request::<NotesDelete>(&NoteIdRequest {
+            note_id: note_id.to_owned(),
+        })
+        .await
+    }
+
+    pub async fn update_note(&self, note_id: &str, text: &str, cw: Option<&str>) -> Result<()> {
+        self.request::<NotesUpdate>(&NotesUpdateRequest {
+            note_id: note_id.to_owned(),
+            text: text.to_owned(),
+            cw: cw.map(str::to_owned),
+        })
+        .await
+    }
+
+    pub async fn renote(&self, note_id: &str) -> Result<Note> {
+        self.create_note(&NoteDraft::new().renote_of(note_id)).await
+    }
+
+    pub async fn unrenote(&self, note_id: &str) -> Result<()> {
+        self.request::<NotesUnrenote>(&NoteIdRequest {
+            note_id: note_id.to_owned(),
+        })
+        .await
+    }
+
+    /// Votes for `choice`, an index into the poll's choices.
+    pub async fn vote(&self, note_id: &str, choice: usize) -> Result<()> {
+        self.request::<NotesPollsVote>(&NotesPollsVoteRequest {
+            note_id: note_id.to_owned(),
+            choice: choice as Int,
+        })
+        .await
+    }
+
+    pub async fn create_reaction(&self, note_id: &str, reaction: &Reaction) -> Result<()> {
+        self.request::<NotesReactionsCreate>(&NotesReactionsCreateRequest {
+            note_id: note_id.to_owned(),
+            reaction: reaction.clone(),
+        })
+        .await
+    }
+
+    /// Like [`Client::create_reaction`], but fails with [`Error::ReactionNotAccepted`]
+    /// without sending if `note` doesn't accept `reaction`.
+    pub async fn react(&self, note: &Note, reaction: &Reaction) -> Result<()> {
+        if let Some(acceptance) = note.reaction_acceptance {
+            if !reaction.is_accepted_by(Some(acceptance)) {
+                return Err(Error::ReactionNotAccepted(acceptance));
+            }
+        }
+        self.create_reaction(&note.id, reaction).await
+    }
+
+    pub async fn delete_reaction(&self, note_id: &str) -> Result<()> {
+        self.request::<NotesReactionsDelete>(&NoteIdRequest {
+            note_id: note_id.to_owned(),
+        })
+        .await
+    }
+
+    /// Lists who reacted to the note, optionally only with `reaction`.
+    ///
+    /// Use [`Client::paginate`] with [`NotesReactions`] to walk all of them.
+    pub async fn note_reactions(
+        &self,
+        note_id: &str,
+        reaction: Option<&Reaction>,
+        pagination: Pagination,
+    ) -> Result<Vec<NoteReaction>> {
+        self.request::<NotesReactions>(&NotesReactionsRequest {
+            note_id: note_id.to_owned(),
+            r#type: reaction.cloned(),
+            pagination,
+        })
+        .await
+    }
+
+    pub async fn conversation(&self, note_id: &str) -> Result<Vec<Note>> {
+        self.request::<NotesConversation>(&NotesConversationRequest {
+            note_id: note_id.to_owned(),
+            ..Default::default()
+        })
+        .await
+    }
+
+    pub async fn read_ltl(&self) -> Result<Vec<Note>> {
+        self.local_timeline(TimelineOptions::default()).await
+    }
+
+    pub async fn home_timeline(&self, options: TimelineOptions) -> Result<Vec<Note>> {
+        self.request::<NotesTimeline>(&options).await
+    }
+
+    pub async fn local_timeline(&self, options: TimelineOptions) -> Result<Vec<Note>> {
+        self.request::<NotesLocalTimeline>(&options).await
+    }
+
+    /// The social timeline.
+    pub async fn hybrid_timeline(&self, options: TimelineOptions) -> Result<Vec<Note>> {
+        self.request::<NotesHybridTimeline>(&options).await
+    }
+
+    pub async fn global_timeline(&self, options: TimelineOptions) -> Result<Vec<Note>> {
+        self.request::<NotesGlobalTimeline>(&options).await
+    }
+
+    pub async fn user_list_timeline(
+        &self,
+        list_id: &str,
+        options: TimelineOptions,
+    ) -> Result<Vec<Note>> {
+        self.request::<NotesUserListTimeline>(&NotesUserListTimelineRequest {
+            list_id: list_id.to_owned(),
+            options,
+        })
+        .await
+    }
+
+    pub async fn channel_timeline(
+        &self,
+        channel_id: &str,
+        options: TimelineOptions,
+    ) -> Result<Vec<Note>> {
+        self.request::<ChannelsTimeline>(&ChannelsTimelineRequest {
+            channel_id: channel_id.to_owned(),
+            options,
+        })
+        .await
+    }
+
+    pub async fn antenna_timeline(
+        &self,
+        antenna_id: &str,
+        options: TimelineOptions,
+    ) -> Result<Vec<Note>> {
+        self.request::<AntennasNotes>(&AntennasNotesRequest {
+            antenna_id: antenna_id.to_owned(),
+            options,
+        })
+        .await
+    }
+
+    pub async fn role_timeline(
+        &self,
+        role_id: &str,
+        options: TimelineOptions,
+    ) -> Result<Vec<Note>> {
+        self.request::<RolesNotes>(&RolesNotesRequest {
+            role_id: role_id.to_owned(),
+            options,
+        })
+        .await
+    }
+
+    /// `tag` is given without the leading `#`.
+    pub async fn hashtag_timeline(&self, tag: &str, options: TimelineOptions) -> Result<Vec<Note>> {
+        self.request::<NotesSearchByTag>(&NotesSearchByTagRequest {
+            tag: tag.to_owned(),
+            options,
+        })
+        .await
+    }
+
+    pub async fn user_notes(&self, user_id: &str, options: TimelineOptions) -> Result<Vec<Note>> {
+        self.request::<UsersNotes>(&UsersNotesRequest {
+            user_id: user_id.to_owned(),
+            options,
+        })
+        .await
+    }
+}
+
+pub struct ClientBuilder {
+    host: String,
+    base_url: Option<Url>,
+    token: Option<String>,
+    http: Option<reqwest::Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    default_headers: HeaderMap,
+    proxy: Option<reqwest::Proxy>,
+}
+
+impl ClientBuilder {
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            base_url: None,
+            token: None,
+            http: None,
+            timeout: None,
+            connect_timeout: None,
+            user_agent: None,
+            default_headers: HeaderMap::new(),
+            proxy: None,
+        }
+    }
+
+    /// Overrides `https://{host}/`, e.g. with `http://localhost:3000/`.
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = Some(base_url);
+        self
     }
+
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Uses a preconfigured client, so connections can be shared with other code.
+    ///
+    /// The timeout, user agent, header and proxy settings of this builder are ignored then.
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Timeout of each whole request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        let mut base_url = match self.base_url {
+            Some(url) => url,
+            None => {
+                let url = format!("https://{}/", self.host);
+                Url::parse(&url).map_err(|_| Error::InvalidUrl(url))?
+            }
+        };
+        if !base_url.path().ends_with('/') {
+            let path = format!("{}/", base_url.path());
+            base_url.set_path(&path);
+        }
+
+        let http = match self.http {
+            Some(http) => http,
+            None => {
+                let mut builder = reqwest::Client::builder().default_headers(self.default_headers);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(Client {
+            http,
+            base_url,
+            token: self.token,
+        })
+    }
+}
+
+/// Decodes a successful response as `T`, or the Misskey error body otherwise.
+///
+/// Endpoints answering `204 No Content` decode as `null`, so `T = ()` works for them.
+async fn decode<T: DeserializeOwned>(res: reqwest::Response) -> Result<T> {
+    let status = res.status();
+    let body = res.bytes().await?;
+
+    if !status.is_success() {
+        return Err(Error::from_response(status, &body));
+    }
+    if body.is_empty() {
+        return Ok(serde_json::from_slice(b"null")?);
+    }
+    Ok(serde_json::from_slice(&body)?)
 }
+
+#[cfg(test)]
+mod tests;
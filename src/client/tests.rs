@@ -0,0 +1,97 @@
+use super::*;
+use crate::{
+    fixtures,
+    mi_entities::note::ReactionAcceptance,
+    mock_server::{MockServer, Response},
+};
+
+#[test]
+fn default_base_url() {
+    let client = Client::new("misskey.example", Some("token")).unwrap();
+
+    assert_eq!(client.base_url().as_str(), "https://misskey.example/");
+    assert_eq!(
+        client.streaming_url().unwrap().as_str(),
+        "wss://misskey.example/stream?i=token"
+    );
+}
+
+#[test]
+fn custom_base_url() {
+    let client = Client::builder("ignored")
+        .base_url(Url::parse("http://localhost:3000/misskey").unwrap())
+        .build()
+        .unwrap();
+
+    assert_eq!(client.base_url().as_str(), "http://localhost:3000/misskey/");
+    assert_eq!(
+        client.url("api/notes/create").unwrap().as_str(),
+        "http://localhost:3000/misskey/api/notes/create"
+    );
+    assert_eq!(
+        client.streaming_url().unwrap().as_str(),
+        "ws://localhost:3000/misskey/stream"
+    );
+}
+
+#[test]
+fn invalid_host() {
+    let res = Client::builder("exa mple").build();
+    assert!(matches!(res, Err(Error::InvalidUrl(_))));
+
+    let res = Client::new("exa mple", None::<String>);
+    assert!(matches!(res, Err(Error::InvalidUrl(_))));
+}
+
+#[tokio::test]
+async fn show_deleted_note() {
+    let server = MockServer::start(|_| {
+        Response::json(fixtures::note(serde_json::json!({
+            "deletedAt": "2025-01-02T00:00:00.000Z"
+        })))
+    })
+    .await;
+
+    let err = server.client().show_note("9xyz000001").await.unwrap_err();
+
+    assert!(matches!(err, Error::NoteDeleted(_)));
+    let req = &server.requests()[0];
+    assert_eq!(req.method, "POST");
+    assert_eq!(req.path, "/api/notes/show");
+    assert_eq!(req.headers["content-type"], "application/json");
+    assert_eq!(req.json()["noteId"], "9xyz000001");
+}
+
+#[tokio::test]
+async fn no_content() {
+    let server = MockServer::start(|_| Response::no_content()).await;
+
+    server.client().delete_note("9abc000002").await.unwrap();
+
+    let req = &server.requests()[0];
+    assert_eq!(req.path, "/api/notes/delete");
+    assert_eq!(req.json()["i"], "token");
+}
+
+#[tokio::test]
+async fn react_like_only() {
+    let server = MockServer::start(|_| Response::no_content()).await;
+    let client = server.client();
+    let note: Note = serde_json::from_value(fixtures::note(serde_json::json!({
+        "reactionAcceptance": "likeOnly"
+    })))
+    .unwrap();
+
+    let err = client
+        .react(&note, &Reaction::local("blobcat"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::ReactionNotAccepted(ReactionAcceptance::LikeOnly)
+    ));
+    assert!(server.requests().is_empty());
+
+    client.react(&note, &Reaction::like()).await.unwrap();
+    assert_eq!(server.requests()[0].path, "/api/notes/reactions/create");
+}
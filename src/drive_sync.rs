@@ -0,0 +1,414 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use futures::{StreamExt, TryStreamExt};
+use md5::{Digest, Md5};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::{info, warn};
+
+use crate::{
+    endpoints::{
+        drive::{DriveFilesRequest, DriveFoldersRequest},
+        DriveFiles, DriveFolders,
+    },
+    error::{Error, Result},
+    mi_entities::DriveFile,
+    Client, DriveUpload,
+};
+
+/// Suffix of downloads in progress. Such files are ignored when scanning.
+const PART_SUFFIX: &str = ".metol-part";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SyncDirection {
+    /// Remote drive to local directory.
+    Pull,
+
+    /// Local directory to remote drive.
+    Push,
+}
+
+/// The remote folder tree, keyed by path relative to the synced folder.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct RemoteTree {
+    pub folders: BTreeMap<PathBuf, String>,
+    pub files: BTreeMap<PathBuf, Vec<DriveFile>>,
+}
+
+/// The local directory tree, keyed by path relative to the synced directory.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct LocalTree {
+    pub dirs: BTreeSet<PathBuf>,
+
+    /// Lowercase hex MD5 of each file.
+    pub files: BTreeMap<PathBuf, String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyncAction {
+    CreateLocalDir(PathBuf),
+
+    Download {
+        path: PathBuf,
+        file: Box<DriveFile>,
+    },
+
+    CreateRemoteFolder(PathBuf),
+
+    /// Uploads `path`, then deletes the remote files it replaces.
+    Upload {
+        path: PathBuf,
+        replaces: Vec<String>,
+    },
+}
+
+/// What a sync would do. Inspect it for a dry run, or pass it to [`DriveSync::execute`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncPlan {
+    pub direction: SyncDirection,
+    pub actions: Vec<SyncAction>,
+
+    /// Files whose MD5 already matches.
+    pub unchanged: usize,
+
+    remote_folders: BTreeMap<PathBuf, String>,
+}
+
+impl SyncPlan {
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+/// Mirrors a drive folder and a local directory.
+///
+/// Files are compared by MD5 and never deleted from the destination unless replaced. An
+/// interrupted sync resumes by planning again: finished files match and are skipped, and
+/// downloads only appear under their name once complete.
+pub struct DriveSync<'a> {
+    client: &'a Client,
+    local_root: PathBuf,
+    remote_root: Option<String>,
+}
+
+impl<'a> DriveSync<'a> {
+    /// Syncs `local_root` with the root folder of the drive.
+    pub fn new(client: &'a Client, local_root: impl Into<PathBuf>) -> Self {
+        Self {
+            client,
+            local_root: local_root.into(),
+            remote_root: None,
+        }
+    }
+
+    /// Syncs with the folder `folder_id` instead of the drive root.
+    pub fn remote_folder(mut self, folder_id: impl Into<String>) -> Self {
+        self.remote_root = Some(folder_id.into());
+        self
+    }
+
+    pub async fn plan(&self, direction: SyncDirection) -> Result<SyncPlan> {
+        let remote = RemoteTree::scan(self.client, self.remote_root.as_deref()).await?;
+        let local = LocalTree::scan(&self.local_root).await?;
+
+        Ok(match direction {
+            SyncDirection::Pull => plan_pull(&remote, &local),
+            SyncDirection::Push => plan_push(&local, &remote),
+        })
+    }
+
+    pub async fn pull(&self) -> Result<SyncPlan> {
+        let plan = self.plan(SyncDirection::Pull).await?;
+        self.execute(&plan).await?;
+        Ok(plan)
+    }
+
+    pub async fn push(&self) -> Result<SyncPlan> {
+        let plan = self.plan(SyncDirection::Push).await?;
+        self.execute(&plan).await?;
+        Ok(plan)
+    }
+
+    pub async fn execute(&self, plan: &SyncPlan) -> Result<()> {
+        let mut folders = plan.remote_folders.clone();
+        if plan.direction == SyncDirection::Pull {
+            tokio::fs::create_dir_all(&self.local_root).await?;
+        }
+
+        for action in &plan.actions {
+            info!("{action:?}");
+            match action {
+                SyncAction::CreateLocalDir(path) => {
+                    tokio::fs::create_dir_all(self.local_root.join(path)).await?;
+                }
+                SyncAction::Download { path, file } => {
+                    self.download(file, &self.local_root.join(path)).await?;
+                }
+                SyncAction::CreateRemoteFolder(path) => {
+                    let parent = self.folder_id(&folders, path.parent());
+                    let name = file_name(path);
+                    let folder = self.client.create_folder(&name, parent.as_deref()).await?;
+                    folders.insert(path.clone(), folder.id);
+                }
+                SyncAction::Upload { path, replaces } => {
+                    let mut upload = DriveUpload::from_path(self.local_root.join(path))
+                        .await?
+                        .name(file_name(path))
+                        .force(true);
+                    if let Some(folder_id) = self.folder_id(&folders, path.parent()) {
+                        upload = upload.folder(folder_id);
+                    }
+                    self.client.upload_file(upload).await?;
+
+                    for file_id in replaces {
+                        self.client.delete_file(file_id).await?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn folder_id(
+        &self,
+        folders: &BTreeMap<PathBuf, String>,
+        path: Option<&Path>,
+    ) -> Option<String> {
+        match path {
+            Some(path) if !path.as_os_str().is_empty() => folders.get(path).cloned(),
+            _ => self.remote_root.clone(),
+        }
+    }
+
+    async fn download(&self, file: &DriveFile, dest: &Path) -> Result<()> {
+        let mut part = dest.as_os_str().to_owned();
+        part.push(PART_SUFFIX);
+        let part = PathBuf::from(part);
+
+        let res = self
+            .client
+            .http_client()
+            .get(&file.url)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let mut out = tokio::fs::File::create(&part).await?;
+        let mut hasher = Md5::new();
+        let mut body = res.bytes_stream();
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk?;
+            hasher.update(&chunk);
+            out.write_all(&chunk).await?;
+        }
+        out.flush().await?;
+        drop(out);
+
+        let md5 = hex(&hasher.finalize());
+        if md5 != file.md5 {
+            tokio::fs::remove_file(&part).await?;
+            return Err(Error::Io(std::io::Error::other(format!(
+                "MD5 mismatch for {}: expected {}, got {md5}",
+                dest.display(),
+                file.md5
+            ))));
+        }
+
+        tokio::fs::rename(&part, dest).await?;
+        Ok(())
+    }
+}
+
+impl RemoteTree {
+    /// Walks `root`, or the drive root if `None`.
+    pub async fn scan(client: &Client, root: Option<&str>) -> Result<Self> {
+        let mut tree = RemoteTree::default();
+        let mut queue = VecDeque::from([(PathBuf::new(), root.map(str::to_owned))]);
+
+        while let Some((path, folder_id)) = queue.pop_front() {
+            let folders: Vec<_> = client
+                .paginate::<DriveFolders>(DriveFoldersRequest {
+                    folder_id: folder_id.clone(),
+                    ..Default::default()
+                })
+                .page_size(100)
+                .into_stream()
+                .try_collect()
+                .await?;
+            for folder in folders {
+                let Some(name) = checked_name(&folder.name) else {
+                    continue;
+                };
+                let child = path.join(name);
+                if tree.folders.contains_key(&child) {
+                    warn!("skipping folder with duplicate name: {child:?}");
+                    continue;
+                }
+                tree.folders.insert(child.clone(), folder.id.clone());
+                queue.push_back((child, Some(folder.id)));
+            }
+
+            let files: Vec<_> = client
+                .paginate::<DriveFiles>(DriveFilesRequest {
+                    folder_id,
+                    ..Default::default()
+                })
+                .page_size(100)
+                .into_stream()
+                .try_collect()
+                .await?;
+            for file in files {
+                let Some(name) = checked_name(&file.name) else {
+                    continue;
+                };
+                tree.files.entry(path.join(name)).or_default().push(file);
+            }
+        }
+
+        Ok(tree)
+    }
+}
+
+impl LocalTree {
+    pub async fn scan(root: &Path) -> Result<Self> {
+        let mut tree = LocalTree::default();
+        if !tokio::fs::try_exists(root).await? {
+            return Ok(tree);
+        }
+        let mut queue = VecDeque::from([PathBuf::new()]);
+
+        while let Some(path) = queue.pop_front() {
+            let mut entries = tokio::fs::read_dir(root.join(&path)).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+                    warn!("skipping non UTF-8 name: {:?}", entry.path());
+                    continue;
+                };
+                if name.ends_with(PART_SUFFIX) {
+                    continue;
+                }
+
+                let child = path.join(&name);
+                // Not following symlinks, which could loop back to an ancestor.
+                let file_type = entry.file_type().await?;
+                if file_type.is_symlink() {
+                    warn!("skipping symlink: {:?}", entry.path());
+                } else if file_type.is_dir() {
+                    tree.dirs.insert(child.clone());
+                    queue.push_back(child);
+                } else if file_type.is_file() {
+                    let md5 = md5_file(&entry.path()).await?;
+                    tree.files.insert(child, md5);
+                }
+            }
+        }
+
+        Ok(tree)
+    }
+}
+
+pub fn plan_pull(remote: &RemoteTree, local: &LocalTree) -> SyncPlan {
+    let mut actions = Vec::new();
+    let mut unchanged = 0;
+
+    for path in remote.folders.keys() {
+        if !local.dirs.contains(path) {
+            actions.push(SyncAction::CreateLocalDir(path.clone()));
+        }
+    }
+    for (path, files) in &remote.files {
+        let local_md5 = local.files.get(path);
+        if files.iter().any(|x| Some(&x.md5) == local_md5) {
+            unchanged += 1;
+            continue;
+        }
+        // Of same-named files, the newest one wins.
+        let Some(newest) = files.iter().max_by_key(|x| x.created_at) else {
+            continue;
+        };
+        actions.push(SyncAction::Download {
+            path: path.clone(),
+            file: Box::new(newest.clone()),
+        });
+    }
+
+    SyncPlan {
+        direction: SyncDirection::Pull,
+        actions,
+        unchanged,
+        remote_folders: remote.folders.clone(),
+    }
+}
+
+pub fn plan_push(local: &LocalTree, remote: &RemoteTree) -> SyncPlan {
+    let mut actions = Vec::new();
+    let mut unchanged = 0;
+
+    // `BTreeSet` order puts parents before their children.
+    for path in &local.dirs {
+        if !remote.folders.contains_key(path) {
+            actions.push(SyncAction::CreateRemoteFolder(path.clone()));
+        }
+    }
+    for (path, md5) in &local.files {
+        let remote_files = remote
+            .files
+            .get(path)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        if remote_files.iter().any(|x| &x.md5 == md5) {
+            unchanged += 1;
+            continue;
+        }
+        actions.push(SyncAction::Upload {
+            path: path.clone(),
+            replaces: remote_files.iter().map(|x| x.id.clone()).collect(),
+        });
+    }
+
+    SyncPlan {
+        direction: SyncDirection::Push,
+        actions,
+        unchanged,
+        remote_folders: remote.folders.clone(),
+    }
+}
+
+/// Rejects remote names that can't be used as a single path component.
+fn checked_name(name: &str) -> Option<&str> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        warn!("skipping unsupported name: {name:?}");
+        return None;
+    }
+    Some(name)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|x| x.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+async fn md5_file(path: &Path) -> std::io::Result<String> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = Md5::new();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("{x:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,300 @@
+use chrono::{TimeZone, Utc};
+
+use super::*;
+use crate::{
+    mi_entities::drive_file::DriveFolder,
+    mock_server::{MockServer, Request, Response},
+};
+
+fn drive_file(id: &str, name: &str, md5: &str, secs: i64) -> DriveFile {
+    DriveFile {
+        id: id.to_owned(),
+        created_at: Utc.timestamp_opt(secs, 0).unwrap(),
+        name: name.to_owned(),
+        md5: md5.to_owned(),
+        url: format!("https://misskey.example/files/{id}"),
+        ..Default::default()
+    }
+}
+
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("metol-drive-sync-{}", uuid::Uuid::new_v4()))
+}
+
+#[test]
+fn pull() {
+    let remote = RemoteTree {
+        folders: BTreeMap::from([
+            (PathBuf::from("a"), "folder-a".to_owned()),
+            (PathBuf::from("a/b"), "folder-b".to_owned()),
+        ]),
+        files: BTreeMap::from([
+            (
+                PathBuf::from("same.png"),
+                vec![drive_file("f1", "same.png", "11", 0)],
+            ),
+            (
+                PathBuf::from("a/changed.png"),
+                vec![
+                    drive_file("f2", "changed.png", "22", 0),
+                    drive_file("f3", "changed.png", "33", 1),
+                ],
+            ),
+        ]),
+    };
+    let local = LocalTree {
+        dirs: BTreeSet::from([PathBuf::from("a")]),
+        files: BTreeMap::from([
+            (PathBuf::from("same.png"), "11".to_owned()),
+            (PathBuf::from("a/changed.png"), "00".to_owned()),
+        ]),
+    };
+
+    let plan = plan_pull(&remote, &local);
+    assert_eq!(plan.unchanged, 1);
+    assert_eq!(
+        plan.actions,
+        vec![
+            SyncAction::CreateLocalDir(PathBuf::from("a/b")),
+            SyncAction::Download {
+                path: PathBuf::from("a/changed.png"),
+                file: Box::new(drive_file("f3", "changed.png", "33", 1)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn push() {
+    let remote = RemoteTree {
+        folders: BTreeMap::from([(PathBuf::from("a"), "folder-a".to_owned())]),
+        files: BTreeMap::from([
+            (
+                PathBuf::from("a/same.png"),
+                vec![drive_file("f1", "same.png", "11", 0)],
+            ),
+            (
+                PathBuf::from("changed.png"),
+                vec![drive_file("f2", "changed.png", "22", 0)],
+            ),
+        ]),
+    };
+    let local = LocalTree {
+        dirs: BTreeSet::from([PathBuf::from("a"), PathBuf::from("c"), PathBuf::from("c/d")]),
+        files: BTreeMap::from([
+            (PathBuf::from("a/same.png"), "11".to_owned()),
+            (PathBuf::from("changed.png"), "33".to_owned()),
+            (PathBuf::from("c/d/new.png"), "44".to_owned()),
+        ]),
+    };
+
+    let plan = plan_push(&local, &remote);
+    assert_eq!(plan.unchanged, 1);
+    assert_eq!(
+        plan.actions,
+        vec![
+            SyncAction::CreateRemoteFolder(PathBuf::from("c")),
+            SyncAction::CreateRemoteFolder(PathBuf::from("c/d")),
+            SyncAction::Upload {
+                path: PathBuf::from("c/d/new.png"),
+                replaces: vec![],
+            },
+            SyncAction::Upload {
+                path: PathBuf::from("changed.png"),
+                replaces: vec!["f2".to_owned()],
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn scan_local() {
+    let root = temp_dir();
+    tokio::fs::create_dir_all(root.join("a")).await.unwrap();
+    tokio::fs::write(root.join("a/hello.txt"), b"hello")
+        .await
+        .unwrap();
+    tokio::fs::write(root.join("a/partial.txt.metol-part"), b"hel")
+        .await
+        .unwrap();
+
+    let tree = LocalTree::scan(&root).await;
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+
+    assert_eq!(
+        tree.unwrap(),
+        LocalTree {
+            dirs: BTreeSet::from([PathBuf::from("a")]),
+            files: BTreeMap::from([(
+                PathBuf::from("a/hello.txt"),
+                "5d41402abc4b2a76b9719d911017c592".to_owned()
+            )]),
+        }
+    );
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn scan_local_skips_symlinks() {
+    let root = temp_dir();
+    tokio::fs::create_dir_all(root.join("a")).await.unwrap();
+    tokio::fs::symlink(&root, root.join("a/loop"))
+        .await
+        .unwrap();
+
+    let tree = LocalTree::scan(&root).await;
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+
+    assert_eq!(
+        tree.unwrap(),
+        LocalTree {
+            dirs: BTreeSet::from([PathBuf::from("a")]),
+            files: BTreeMap::new(),
+        }
+    );
+}
+
+fn json(value: impl serde::Serialize) -> Response {
+    Response::json(serde_json::to_value(value).unwrap())
+}
+
+/// The remote drive of the mock server. `bad.txt` is served with a wrong MD5.
+fn drive(req: &Request) -> Response {
+    let host = &req.headers["host"];
+    let remote_file = |id: &str, name: &str, md5: &str| {
+        let mut file = drive_file(id, name, md5, 0);
+        file.url = format!("http://{host}/files/{id}");
+        file
+    };
+    let folder = |id: &str, name: &str| DriveFolder {
+        id: id.to_owned(),
+        name: name.to_owned(),
+        ..Default::default()
+    };
+
+    match req.path.as_str() {
+        "/api/drive/folders" => match req.json()["folderId"].as_str() {
+            None => json(vec![folder("fa", "a"), folder("f0", "a")]),
+            _ => json(Vec::<DriveFolder>::new()),
+        },
+        "/api/drive/files" => match req.json()["folderId"].as_str() {
+            None => json(vec![remote_file(
+                "hello",
+                "hello.txt",
+                "5d41402abc4b2a76b9719d911017c592",
+            )]),
+            Some("fa") => json(vec![remote_file("bad", "bad.txt", "0".repeat(32).as_str())]),
+            _ => json(Vec::<DriveFile>::new()),
+        },
+        "/api/drive/folders/create" => json(folder("fc", "c")),
+        "/api/drive/files/create" => json(remote_file("new", "new.txt", "")),
+        "/api/drive/files/delete" => Response::no_content(),
+        path => Response {
+            status: 200,
+            content_type: "application/octet-stream",
+            body: match path {
+                "/files/hello" => b"hello".to_vec(),
+                _ => b"oops".to_vec(),
+            },
+        },
+    }
+}
+
+#[tokio::test]
+async fn scan_remote() {
+    let server = MockServer::start(drive).await;
+    let client = server.client();
+
+    let tree = RemoteTree::scan(&client, None).await.unwrap();
+
+    assert_eq!(
+        tree.folders,
+        BTreeMap::from([(PathBuf::from("a"), "fa".to_owned())])
+    );
+    assert_eq!(
+        tree.files.keys().collect::<Vec<_>>(),
+        [&PathBuf::from("a/bad.txt"), &PathBuf::from("hello.txt")]
+    );
+    // The duplicate `a` isn't walked.
+    assert!(server
+        .requests()
+        .iter()
+        .all(|x| x.json()["folderId"] != "f0"));
+}
+
+#[tokio::test]
+async fn execute_pull() {
+    let server = MockServer::start(drive).await;
+    let client = server.client();
+    let root = temp_dir();
+    let sync = DriveSync::new(&client, &root);
+
+    let mut plan = sync.plan(SyncDirection::Pull).await.unwrap();
+    plan.actions
+        .retain(|x| !matches!(x, SyncAction::Download { path, .. } if path.ends_with("bad.txt")));
+    let res = sync.execute(&plan).await;
+    let hello = tokio::fs::read(root.join("hello.txt")).await;
+    let local = LocalTree::scan(&root).await;
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+
+    res.unwrap();
+    assert_eq!(hello.unwrap(), b"hello");
+    assert_eq!(local.unwrap().dirs, BTreeSet::from([PathBuf::from("a")]));
+}
+
+#[tokio::test]
+async fn execute_md5_mismatch() {
+    let server = MockServer::start(drive).await;
+    let client = server.client();
+    let root = temp_dir();
+
+    let res = DriveSync::new(&client, &root).pull().await;
+    let bad = tokio::fs::try_exists(root.join("a/bad.txt")).await.unwrap();
+    let part = tokio::fs::try_exists(root.join("a/bad.txt.metol-part"))
+        .await
+        .unwrap();
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+
+    assert!(matches!(res, Err(Error::Io(_))));
+    assert!(!bad);
+    assert!(!part);
+}
+
+#[tokio::test]
+async fn execute_push() {
+    let server = MockServer::start(drive).await;
+    let client = server.client();
+    let root = temp_dir();
+    tokio::fs::create_dir_all(root.join("c")).await.unwrap();
+    tokio::fs::write(root.join("c/new.txt"), b"new")
+        .await
+        .unwrap();
+    tokio::fs::write(root.join("hello.txt"), b"changed")
+        .await
+        .unwrap();
+
+    let res = DriveSync::new(&client, &root).push().await;
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+
+    let plan = res.unwrap();
+    assert_eq!(plan.unchanged, 0);
+    let reqs: Vec<_> = server
+        .requests()
+        .into_iter()
+        .filter(|x| !matches!(x.path.as_str(), "/api/drive/folders" | "/api/drive/files"))
+        .collect();
+    let paths: Vec<_> = reqs.iter().map(|x| x.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/api/drive/folders/create",
+            "/api/drive/files/create",
+            "/api/drive/files/create",
+            "/api/drive/files/delete",
+        ]
+    );
+    assert_eq!(reqs[0].json()["name"], "c");
+    let upload = String::from_utf8_lossy(&reqs[1].body);
+    assert!(upload.contains("name=\"folderId\"\r\n\r\nfc\r\n"));
+    assert_eq!(reqs[3].json()["fileId"], "hello");
+}
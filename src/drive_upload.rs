@@ -0,0 +1,162 @@
+use std::{fmt, io::Cursor, path::Path, pin::Pin, sync::Arc};
+
+use bytes::Bytes;
+use futures::stream;
+use reqwest::multipart::{Form, Part};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+type ProgressFn = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UploadProgress {
+    /// Bytes handed to the transport so far.
+    pub sent: u64,
+
+    /// `None` when uploading from a reader of unknown length.
+    pub total: Option<u64>,
+}
+
+/// Request of `drive/files/create`, sent by [`Client::upload_file`](crate::Client::upload_file).
+pub struct DriveUpload {
+    reader: Pin<Box<dyn AsyncRead + Send>>,
+    len: Option<u64>,
+    name: Option<String>,
+    folder_id: Option<String>,
+    comment: Option<String>,
+    is_sensitive: Option<bool>,
+    force: Option<bool>,
+    on_progress: Option<ProgressFn>,
+}
+
+impl DriveUpload {
+    /// Streams the file at `path`, named after its file name.
+    pub async fn from_path(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        let file = tokio::fs::File::open(path).await?;
+        let len = file.metadata().await?.len();
+        let upload = Self::from_reader(file, Some(len));
+
+        Ok(match path.file_name() {
+            Some(name) => upload.name(name.to_string_lossy()),
+            None => upload,
+        })
+    }
+
+    pub fn from_bytes(bytes: impl Into<Bytes>) -> Self {
+        let bytes = bytes.into();
+        let len = bytes.len() as u64;
+        Self::from_reader(Cursor::new(bytes), Some(len))
+    }
+
+    /// Streams `reader`. Give `len` if known so the server gets a `Content-Length`.
+    pub fn from_reader(reader: impl AsyncRead + Send + 'static, len: Option<u64>) -> Self {
+        Self {
+            reader: Box::pin(reader),
+            len,
+            name: None,
+            folder_id: None,
+            comment: None,
+            is_sensitive: None,
+            force: None,
+            on_progress: None,
+        }
+    }
+
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn folder(mut self, folder_id: impl Into<String>) -> Self {
+        self.folder_id = Some(folder_id.into());
+        self
+    }
+
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    pub fn sensitive(mut self, is_sensitive: bool) -> Self {
+        self.is_sensitive = Some(is_sensitive);
+        self
+    }
+
+    /// Uploads even if a file with the same hash already exists in the drive.
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = Some(force);
+        self
+    }
+
+    /// Called after each chunk is read. Send to a channel from here to observe it elsewhere.
+    pub fn on_progress(mut self, f: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(f));
+        self
+    }
+
+    pub(crate) fn into_form(self, token: &str) -> Form {
+        let total = self.len;
+        let on_progress = self.on_progress;
+        let chunks = stream::try_unfold((self.reader, 0u64), move |(mut reader, sent)| {
+            let on_progress = on_progress.clone();
+            async move {
+                let mut buf = vec![0; CHUNK_SIZE];
+                let n = reader.read(&mut buf).await?;
+                if n == 0 {
+                    return Ok::<_, std::io::Error>(None);
+                }
+                buf.truncate(n);
+
+                let sent = sent + n as u64;
+                if let Some(f) = &on_progress {
+                    f(UploadProgress { sent, total });
+                }
+                Ok(Some((Bytes::from(buf), (reader, sent))))
+            }
+        });
+
+        let body = reqwest::Body::wrap_stream(chunks);
+        let name = self.name.unwrap_or_else(|| "untitled".to_owned());
+        let file = match total {
+            Some(len) => Part::stream_with_length(body, len),
+            None => Part::stream(body),
+        }
+        .file_name(name.clone());
+
+        let mut form = Form::new()
+            .text("i", token.to_owned())
+            .text("name", name)
+            .part("file", file);
+        if let Some(folder_id) = self.folder_id {
+            form = form.text("folderId", folder_id);
+        }
+        if let Some(comment) = self.comment {
+            form = form.text("comment", comment);
+        }
+        if let Some(is_sensitive) = self.is_sensitive {
+            form = form.text("isSensitive", is_sensitive.to_string());
+        }
+        if let Some(force) = self.force {
+            form = form.text("force", force.to_string());
+        }
+        form
+    }
+}
+
+impl fmt::Debug for DriveUpload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DriveUpload")
+            .field("len", &self.len)
+            .field("name", &self.name)
+            .field("folder_id", &self.folder_id)
+            .field("comment", &self.comment)
+            .field("is_sensitive", &self.is_sensitive)
+            .field("force", &self.force)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests;
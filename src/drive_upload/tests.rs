@@ -0,0 +1,141 @@
+use std::sync::Mutex;
+
+use crate::{
+    mi_entities::DriveFile,
+    mock_server::{MockServer, Response},
+    Client,
+};
+
+use super::*;
+
+async fn server() -> MockServer {
+    MockServer::start(|_| {
+        let file = DriveFile {
+            id: "file".to_owned(),
+            ..Default::default()
+        };
+        Response::json(serde_json::to_value(file).unwrap())
+    })
+    .await
+}
+
+/// The value of the multipart field `name`.
+fn field(body: &[u8], name: &str) -> Option<String> {
+    let body = String::from_utf8_lossy(body);
+    let start = body.find(&format!("form-data; name=\"{name}\""))?;
+    let rest = &body[start..];
+    let value = &rest[rest.find("\r\n\r\n")? + 4..];
+    Some(value[..value.find("\r\n--")?].to_owned())
+}
+
+fn progress_log(upload: DriveUpload) -> (DriveUpload, Arc<Mutex<Vec<UploadProgress>>>) {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let upload = upload.on_progress({
+        let log = log.clone();
+        move |x| log.lock().unwrap().push(x)
+    });
+    (upload, log)
+}
+
+#[tokio::test]
+async fn form_fields() {
+    let server = server().await;
+    let upload = DriveUpload::from_bytes(&b"hello"[..])
+        .name("hello.txt")
+        .folder("folder")
+        .comment("a comment")
+        .sensitive(true)
+        .force(false);
+
+    let file = server.client().upload_file(upload).await.unwrap();
+    assert_eq!(file.id, "file");
+
+    let req = &server.requests()[0];
+    assert_eq!(req.path, "/api/drive/files/create");
+    assert_eq!(field(&req.body, "i").as_deref(), Some("token"));
+    assert_eq!(field(&req.body, "name").as_deref(), Some("hello.txt"));
+    assert_eq!(field(&req.body, "folderId").as_deref(), Some("folder"));
+    assert_eq!(field(&req.body, "comment").as_deref(), Some("a comment"));
+    assert_eq!(field(&req.body, "isSensitive").as_deref(), Some("true"));
+    assert_eq!(field(&req.body, "force").as_deref(), Some("false"));
+
+    assert_eq!(
+        field(&req.body, "file\"; filename=\"hello.txt").as_deref(),
+        Some("hello")
+    );
+}
+
+#[tokio::test]
+async fn default_fields() {
+    let server = server().await;
+
+    server
+        .client()
+        .upload_file(DriveUpload::from_bytes(&b"hello"[..]))
+        .await
+        .unwrap();
+
+    let req = &server.requests()[0];
+    assert_eq!(field(&req.body, "name").as_deref(), Some("untitled"));
+    assert_eq!(field(&req.body, "folderId"), None);
+    assert_eq!(field(&req.body, "comment"), None);
+    assert_eq!(field(&req.body, "isSensitive"), None);
+    assert_eq!(field(&req.body, "force"), None);
+}
+
+#[tokio::test]
+async fn progress_from_bytes() {
+    let server = server().await;
+    let len = CHUNK_SIZE * 2 + 100;
+    let (upload, log) = progress_log(DriveUpload::from_bytes(vec![b'x'; len]));
+
+    server.client().upload_file(upload).await.unwrap();
+
+    let total = Some(len as u64);
+    assert_eq!(
+        *log.lock().unwrap(),
+        [
+            UploadProgress {
+                sent: CHUNK_SIZE as u64,
+                total
+            },
+            UploadProgress {
+                sent: CHUNK_SIZE as u64 * 2,
+                total
+            },
+            UploadProgress {
+                sent: len as u64,
+                total
+            },
+        ]
+    );
+    let req = &server.requests()[0];
+    assert_eq!(
+        field(&req.body, "file\"; filename=\"untitled").map(|x| x.len()),
+        Some(len)
+    );
+}
+
+#[tokio::test]
+async fn progress_from_reader() {
+    let server = server().await;
+    let (upload, log) = progress_log(DriveUpload::from_reader(&b"hello"[..], None));
+
+    server.client().upload_file(upload).await.unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        [UploadProgress {
+            sent: 5,
+            total: None
+        }]
+    );
+}
+
+#[tokio::test]
+async fn requires_token() {
+    let client = Client::builder("misskey.example").build().unwrap();
+    let res = client.upload_file(DriveUpload::from_bytes(&b""[..])).await;
+
+    assert!(matches!(res, Err(crate::Error::MissingToken)));
+}
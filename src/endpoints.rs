@@ -0,0 +1,68 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Implements [`PaginatedEndpoint`](crate::paginator::PaginatedEndpoint) for an endpoint
+/// returning `Vec<$item>`, whose request holds the [`Pagination`](crate::Pagination) at
+/// `req.$field`. `id_only` marks endpoints that ignore `sinceDate` and `untilDate`.
+macro_rules! paginated {
+    (@impl $endpoint:ty, $item:ty, $date_cursor:literal, $($field:ident).+) => {
+        impl $crate::paginator::PaginatedEndpoint for $endpoint {
+            type Item = $item;
+
+            const DATE_CURSOR: bool = $date_cursor;
+
+            fn pagination(req: &mut Self::Request) -> &mut $crate::paginator::Pagination {
+                &mut req.$($field).+
+            }
+
+            fn into_items(res: Self::Response) -> Vec<Self::Item> {
+                res
+            }
+        }
+    };
+    ($endpoint:ty, $item:ty, $($field:ident).+, id_only) => {
+        paginated!(@impl $endpoint, $item, false, $($field).+);
+    };
+    ($endpoint:ty, $item:ty, $($field:ident).+) => {
+        paginated!(@impl $endpoint, $item, true, $($field).+);
+    };
+}
+
+pub mod antennas;
+pub mod channels;
+pub mod drive;
+pub mod notes;
+pub mod roles;
+pub mod users;
+
+pub use antennas::AntennasNotes;
+pub use channels::ChannelsTimeline;
+pub use drive::{
+    DriveFiles, DriveFilesDelete, DriveFilesFindByHash, DriveFilesShow, DriveFilesUpdate,
+    DriveFolders, DriveFoldersCreate, DriveFoldersDelete, DriveFoldersUpdate,
+};
+pub use notes::{
+    NotesConversation, NotesCreate, NotesDelete, NotesGlobalTimeline, NotesHybridTimeline,
+    NotesLocalTimeline, NotesPollsVote, NotesReactions, NotesReactionsCreate, NotesReactionsDelete,
+    NotesSearchByTag, NotesShow, NotesTimeline, NotesUnrenote, NotesUpdate, NotesUserListTimeline,
+    TimelineOptions,
+};
+pub use roles::RolesNotes;
+pub use users::UsersNotes;
+
+/// A REST endpoint under `/api/`.
+///
+/// Implement this on a marker type to call endpoints this crate doesn't cover through
+/// [`Client::request`](crate::Client::request).
+pub trait Endpoint {
+    type Request: Serialize;
+    type Response: DeserializeOwned;
+
+    /// Path relative to `/api/`, e.g. `notes/create`.
+    const PATH: &'static str;
+
+    /// Whether the endpoint fails without the `i` token.
+    const REQUIRES_AUTH: bool;
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+use crate::mi_entities::Note;
+
+use super::{notes::TimelineOptions, Endpoint};
+
+pub struct AntennasNotes;
+
+impl Endpoint for AntennasNotes {
+    type Request = AntennasNotesRequest;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "antennas/notes";
+    const REQUIRES_AUTH: bool = true;
+}
+
+paginated!(AntennasNotes, Note, options.pagination);
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AntennasNotesRequest {
+    pub antenna_id: String,
+
+    #[serde(flatten)]
+    pub options: TimelineOptions,
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+use crate::mi_entities::Note;
+
+use super::{notes::TimelineOptions, Endpoint};
+
+pub struct ChannelsTimeline;
+
+impl Endpoint for ChannelsTimeline {
+    type Request = ChannelsTimelineRequest;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "channels/timeline";
+    const REQUIRES_AUTH: bool = false;
+}
+
+paginated!(ChannelsTimeline, Note, options.pagination);
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelsTimelineRequest {
+    pub channel_id: String,
+
+    #[serde(flatten)]
+    pub options: TimelineOptions,
+}
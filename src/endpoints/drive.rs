@@ -0,0 +1,177 @@
+use serde::Serialize;
+
+use crate::{
+    mi_entities::{drive_file::DriveFolder, DriveFile},
+    paginator::Pagination,
+};
+
+use super::Endpoint;
+
+pub struct DriveFiles;
+
+impl Endpoint for DriveFiles {
+    type Request = DriveFilesRequest;
+    type Response = Vec<DriveFile>;
+    const PATH: &'static str = "drive/files";
+    const REQUIRES_AUTH: bool = true;
+}
+
+paginated!(DriveFiles, DriveFile, pagination, id_only);
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFilesRequest {
+    /// `None` lists the root folder.
+    pub folder_id: Option<String>,
+
+    /// MIME type filter such as `image/*`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+
+    /// Only ids are honored. `sinceDate` and `untilDate` are ignored by Misskey.
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
+
+pub struct DriveFolders;
+
+impl Endpoint for DriveFolders {
+    type Request = DriveFoldersRequest;
+    type Response = Vec<DriveFolder>;
+    const PATH: &'static str = "drive/folders";
+    const REQUIRES_AUTH: bool = true;
+}
+
+paginated!(DriveFolders, DriveFolder, pagination, id_only);
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFoldersRequest {
+    /// `None` lists the root folder.
+    pub folder_id: Option<String>,
+
+    /// Only ids are honored. `sinceDate` and `untilDate` are ignored by Misskey.
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
+
+pub struct DriveFilesShow;
+
+impl Endpoint for DriveFilesShow {
+    type Request = FileIdRequest;
+    type Response = DriveFile;
+    const PATH: &'static str = "drive/files/show";
+    const REQUIRES_AUTH: bool = true;
+}
+
+pub struct DriveFilesUpdate;
+
+impl Endpoint for DriveFilesUpdate {
+    type Request = DriveFilesUpdateRequest;
+    type Response = DriveFile;
+    const PATH: &'static str = "drive/files/update";
+    const REQUIRES_AUTH: bool = true;
+}
+
+/// Fields left as `None` are kept. `Some(None)` clears nullable fields, or moves the file
+/// to the root folder.
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFilesUpdateRequest {
+    pub file_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<Option<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_sensitive: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<Option<String>>,
+}
+
+pub struct DriveFilesDelete;
+
+impl Endpoint for DriveFilesDelete {
+    type Request = FileIdRequest;
+    type Response = ();
+    const PATH: &'static str = "drive/files/delete";
+    const REQUIRES_AUTH: bool = true;
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIdRequest {
+    pub file_id: String,
+}
+
+pub struct DriveFilesFindByHash;
+
+impl Endpoint for DriveFilesFindByHash {
+    type Request = DriveFilesFindByHashRequest;
+    type Response = Vec<DriveFile>;
+    const PATH: &'static str = "drive/files/find-by-hash";
+    const REQUIRES_AUTH: bool = true;
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFilesFindByHashRequest {
+    pub md5: String,
+}
+
+pub struct DriveFoldersCreate;
+
+impl Endpoint for DriveFoldersCreate {
+    type Request = DriveFoldersCreateRequest;
+    type Response = DriveFolder;
+    const PATH: &'static str = "drive/folders/create";
+    const REQUIRES_AUTH: bool = true;
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFoldersCreateRequest {
+    pub name: String,
+    pub parent_id: Option<String>,
+}
+
+pub struct DriveFoldersUpdate;
+
+impl Endpoint for DriveFoldersUpdate {
+    type Request = DriveFoldersUpdateRequest;
+    type Response = DriveFolder;
+    const PATH: &'static str = "drive/folders/update";
+    const REQUIRES_AUTH: bool = true;
+}
+
+/// Fields left as `None` are kept. `parent_id: Some(None)` moves the folder to the root.
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFoldersUpdateRequest {
+    pub folder_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Option<String>>,
+}
+
+pub struct DriveFoldersDelete;
+
+impl Endpoint for DriveFoldersDelete {
+    type Request = FolderIdRequest;
+    type Response = ();
+    const PATH: &'static str = "drive/folders/delete";
+    const REQUIRES_AUTH: bool = true;
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderIdRequest {
+    pub folder_id: String,
+}
@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::Int,
+    mi_entities::{note::NoteReaction, Note},
+    note_draft::NoteDraft,
+    paginator::Pagination,
+    reaction::Reaction,
+};
+
+use super::Endpoint;
+
+pub struct NotesCreate;
+
+impl Endpoint for NotesCreate {
+    type Request = NoteDraft;
+    type Response = NotesCreateResponse;
+    const PATH: &'static str = "notes/create";
+    const REQUIRES_AUTH: bool = true;
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesCreateResponse {
+    pub created_note: Note,
+}
+
+pub struct NotesConversation;
+
+impl Endpoint for NotesConversation {
+    type Request = NotesConversationRequest;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "notes/conversation";
+    const REQUIRES_AUTH: bool = false;
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesConversationRequest {
+    pub note_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<Int>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<Int>,
+}
+
+/// Options shared by the timeline endpoints.
+///
+/// Each endpoint only honors the flags Misskey defines for it.
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_files: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_renotes: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_replies: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_partial: Option<bool>,
+
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
+
+pub struct NotesTimeline;
+
+impl Endpoint for NotesTimeline {
+    type Request = TimelineOptions;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "notes/timeline";
+    const REQUIRES_AUTH: bool = true;
+}
+
+paginated!(NotesTimeline, Note, pagination);
+
+pub struct NotesLocalTimeline;
+
+impl Endpoint for NotesLocalTimeline {
+    type Request = TimelineOptions;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "notes/local-timeline";
+    const REQUIRES_AUTH: bool = false;
+}
+
+paginated!(NotesLocalTimeline, Note, pagination);
+
+pub struct NotesHybridTimeline;
+
+impl Endpoint for NotesHybridTimeline {
+    type Request = TimelineOptions;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "notes/hybrid-timeline";
+    const REQUIRES_AUTH: bool = true;
+}
+
+paginated!(NotesHybridTimeline, Note, pagination);
+
+pub struct NotesGlobalTimeline;
+
+impl Endpoint for NotesGlobalTimeline {
+    type Request = TimelineOptions;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "notes/global-timeline";
+    const REQUIRES_AUTH: bool = false;
+}
+
+paginated!(NotesGlobalTimeline, Note, pagination);
+
+pub struct NotesUserListTimeline;
+
+impl Endpoint for NotesUserListTimeline {
+    type Request = NotesUserListTimelineRequest;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "notes/user-list-timeline";
+    const REQUIRES_AUTH: bool = true;
+}
+
+paginated!(NotesUserListTimeline, Note, options.pagination);
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesUserListTimelineRequest {
+    pub list_id: String,
+
+    #[serde(flatten)]
+    pub options: TimelineOptions,
+}
+
+pub struct NotesSearchByTag;
+
+impl Endpoint for NotesSearchByTag {
+    type Request = NotesSearchByTagRequest;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "notes/search-by-tag";
+    const REQUIRES_AUTH: bool = false;
+}
+
+paginated!(NotesSearchByTag, Note, options.pagination);
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesSearchByTagRequest {
+    pub tag: String,
+
+    #[serde(flatten)]
+    pub options: TimelineOptions,
+}
+
+pub struct NotesShow;
+
+impl Endpoint for NotesShow {
+    type Request = NoteIdRequest;
+    type Response = Note;
+    const PATH: &'static str = "notes/show";
+    const REQUIRES_AUTH: bool = false;
+}
+
+pub struct NotesDelete;
+
+impl Endpoint for NotesDelete {
+    type Request = NoteIdRequest;
+    type Response = ();
+    const PATH: &'static str = "notes/delete";
+    const REQUIRES_AUTH: bool = true;
+}
+
+/// Editing is only available on servers whose role policies allow it.
+pub struct NotesUpdate;
+
+impl Endpoint for NotesUpdate {
+    type Request = NotesUpdateRequest;
+    type Response = ();
+    const PATH: &'static str = "notes/update";
+    const REQUIRES_AUTH: bool = true;
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesUpdateRequest {
+    pub note_id: String,
+    pub text: String,
+    pub cw: Option<String>,
+}
+
+/// Deletes all renotes of the note made by the user.
+pub struct NotesUnrenote;
+
+impl Endpoint for NotesUnrenote {
+    type Request = NoteIdRequest;
+    type Response = ();
+    const PATH: &'static str = "notes/unrenote";
+    const REQUIRES_AUTH: bool = true;
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteIdRequest {
+    pub note_id: String,
+}
+
+pub struct NotesPollsVote;
+
+impl Endpoint for NotesPollsVote {
+    type Request = NotesPollsVoteRequest;
+    type Response = ();
+    const PATH: &'static str = "notes/polls/vote";
+    const REQUIRES_AUTH: bool = true;
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesPollsVoteRequest {
+    pub note_id: String,
+
+    /// Index into `NotePoll::choices`.
+    pub choice: Int,
+}
+
+pub struct NotesReactionsCreate;
+
+impl Endpoint for NotesReactionsCreate {
+    type Request = NotesReactionsCreateRequest;
+    type Response = ();
+    const PATH: &'static str = "notes/reactions/create";
+    const REQUIRES_AUTH: bool = true;
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesReactionsCreateRequest {
+    pub note_id: String,
+    pub reaction: Reaction,
+}
+
+pub struct NotesReactionsDelete;
+
+impl Endpoint for NotesReactionsDelete {
+    type Request = NoteIdRequest;
+    type Response = ();
+    const PATH: &'static str = "notes/reactions/delete";
+    const REQUIRES_AUTH: bool = true;
+}
+
+/// Lists who reacted to a note.
+pub struct NotesReactions;
+
+impl Endpoint for NotesReactions {
+    type Request = NotesReactionsRequest;
+    type Response = Vec<NoteReaction>;
+    const PATH: &'static str = "notes/reactions";
+    const REQUIRES_AUTH: bool = false;
+}
+
+paginated!(NotesReactions, NoteReaction, pagination);
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotesReactionsRequest {
+    pub note_id: String,
+
+    /// Only lists this reaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<Reaction>,
+
+    #[serde(flatten)]
+    pub pagination: Pagination,
+}
@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+use crate::mi_entities::Note;
+
+use super::{notes::TimelineOptions, Endpoint};
+
+pub struct RolesNotes;
+
+impl Endpoint for RolesNotes {
+    type Request = RolesNotesRequest;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "roles/notes";
+    const REQUIRES_AUTH: bool = true;
+}
+
+paginated!(RolesNotes, Note, options.pagination);
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RolesNotesRequest {
+    pub role_id: String,
+
+    #[serde(flatten)]
+    pub options: TimelineOptions,
+}
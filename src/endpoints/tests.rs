@@ -0,0 +1,158 @@
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+
+use futures::TryStreamExt;
+
+use super::{
+    drive::{DriveFilesRequest, DriveFilesUpdateRequest, DriveFoldersUpdateRequest},
+    notes::{NotesSearchByTagRequest, NotesUserListTimelineRequest},
+    users::UsersNotesRequest,
+    *,
+};
+use crate::{
+    mi_entities::DriveFile,
+    mock_server::{MockServer, Request, Response},
+    paginator::{Cursor, PaginatedEndpoint},
+    Pagination,
+};
+
+#[test]
+fn timeline_options_default() {
+    let value = serde_json::to_value(TimelineOptions::default()).unwrap();
+    assert_eq!(value, json!({}));
+}
+
+#[test]
+fn timeline_options() {
+    let options = TimelineOptions {
+        with_files: Some(true),
+        with_renotes: Some(false),
+        with_replies: None,
+        allow_partial: Some(true),
+        pagination: Pagination {
+            limit: Some(20),
+            until_id: Some("9abc000001".to_owned()),
+            since_date: Some(Utc.timestamp_opt(1_700_000_000, 0).unwrap()),
+            ..Default::default()
+        },
+    };
+
+    assert_eq!(
+        serde_json::to_value(options).unwrap(),
+        json!({
+            "withFiles": true,
+            "withRenotes": false,
+            "allowPartial": true,
+            "limit": 20,
+            "untilId": "9abc000001",
+            "sinceDate": 1_700_000_000_000i64,
+        })
+    );
+}
+
+#[test]
+fn timeline_requests_flatten_options() {
+    let options = TimelineOptions {
+        with_files: Some(true),
+        pagination: Pagination {
+            limit: Some(10),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let list = NotesUserListTimelineRequest {
+        list_id: "list".to_owned(),
+        options: options.clone(),
+    };
+    assert_eq!(
+        serde_json::to_value(list).unwrap(),
+        json!({"listId": "list", "withFiles": true, "limit": 10})
+    );
+
+    let tag = NotesSearchByTagRequest {
+        tag: "misskey".to_owned(),
+        options: options.clone(),
+    };
+    assert_eq!(
+        serde_json::to_value(tag).unwrap(),
+        json!({"tag": "misskey", "withFiles": true, "limit": 10})
+    );
+
+    let user = UsersNotesRequest {
+        user_id: "user".to_owned(),
+        options,
+    };
+    assert_eq!(
+        serde_json::to_value(user).unwrap(),
+        json!({"userId": "user", "withFiles": true, "limit": 10})
+    );
+}
+
+#[test]
+fn paginated_field() {
+    let mut req = UsersNotesRequest::default();
+    UsersNotes::pagination(&mut req).until_id = Some("9abc000001".to_owned());
+
+    assert_eq!(
+        req.options.pagination.until_id.as_deref(),
+        Some("9abc000001")
+    );
+}
+
+#[test]
+fn drive_update_requests() {
+    let req = DriveFilesUpdateRequest {
+        file_id: "file".to_owned(),
+        folder_id: Some(None),
+        comment: Some(Some("a comment".to_owned())),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_value(req).unwrap(),
+        json!({"fileId": "file", "folderId": null, "comment": "a comment"})
+    );
+
+    let req = DriveFoldersUpdateRequest {
+        folder_id: "folder".to_owned(),
+        name: Some("renamed".to_owned()),
+        parent_id: None,
+    };
+    assert_eq!(
+        serde_json::to_value(req).unwrap(),
+        json!({"folderId": "folder", "name": "renamed"})
+    );
+}
+
+#[tokio::test]
+async fn drive_files_walk_by_id() {
+    let server = MockServer::start(|req| {
+        let files: Vec<_> = match req.json().get("untilId") {
+            None => vec!["f3", "f2"],
+            Some(_) => vec!["f1"],
+        }
+        .into_iter()
+        .map(|id| DriveFile {
+            id: id.to_owned(),
+            ..Default::default()
+        })
+        .collect();
+        Response::json(serde_json::to_value(files).unwrap())
+    })
+    .await;
+    let client = server.client();
+
+    let files: Vec<_> = client
+        .paginate::<DriveFiles>(DriveFilesRequest::default())
+        .page_size(2)
+        .cursor(Cursor::Date)
+        .into_stream()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(files.len(), 3);
+    let reqs: Vec<_> = server.requests().iter().map(Request::json).collect();
+    assert_eq!(reqs[1]["untilId"], "f2");
+    assert!(reqs.iter().all(|x| x.get("untilDate").is_none()));
+}
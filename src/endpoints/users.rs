@@ -0,0 +1,25 @@
+use serde::Serialize;
+
+use crate::mi_entities::Note;
+
+use super::{notes::TimelineOptions, Endpoint};
+
+pub struct UsersNotes;
+
+impl Endpoint for UsersNotes {
+    type Request = UsersNotesRequest;
+    type Response = Vec<Note>;
+    const PATH: &'static str = "users/notes";
+    const REQUIRES_AUTH: bool = false;
+}
+
+paginated!(UsersNotes, Note, options.pagination);
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsersNotesRequest {
+    pub user_id: String,
+
+    #[serde(flatten)]
+    pub options: TimelineOptions,
+}
@@ -0,0 +1,199 @@
+use std::fmt;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::mi_entities::{note::ReactionAcceptance, Note};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// `RATE_LIMIT_EXCEEDED`
+    RateLimited(Box<ApiError>),
+
+    /// `CREDENTIAL_REQUIRED` or `AUTHENTICATION_FAILED`
+    Unauthorized(Box<ApiError>),
+
+    /// `NO_SUCH_NOTE`
+    NoSuchNote(Box<ApiError>),
+
+    /// `PERMISSION_DENIED` or `ROLE_PERMISSION_DENIED`
+    PermissionDenied(Box<ApiError>),
+
+    /// Any other error reported by the server.
+    Api(Box<ApiError>),
+
+    /// The note was found but has been deleted.
+    NoteDeleted(Box<Note>),
+
+    /// The note was found but its content is hidden from the viewer.
+    NoteHidden(Box<Note>),
+
+    /// The note doesn't accept the reaction. Checked before sending.
+    ReactionNotAccepted(ReactionAcceptance),
+
+    /// The endpoint requires a token but the client has none.
+    MissingToken,
+
+    /// The host or base URL doesn't form a valid URL.
+    InvalidUrl(String),
+
+    /// The server returned an error status without a Misskey error body.
+    Status(StatusCode, String),
+
+    Transport(reqwest::Error),
+
+    Decode(serde_json::Error),
+
+    Io(std::io::Error),
+}
+
+/// The `error` object of a failed API response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiError {
+    pub code: String,
+
+    pub id: String,
+
+    pub message: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<serde_json::Value>,
+
+    #[serde(skip)]
+    pub status: Option<StatusCode>,
+}
+
+impl Error {
+    pub(crate) fn from_response(status: StatusCode, body: &[u8]) -> Self {
+        #[derive(Deserialize)]
+        struct ErrBody {
+            error: ApiError,
+        }
+
+        match serde_json::from_slice::<ErrBody>(body) {
+            Ok(ErrBody { mut error }) => {
+                error.status = Some(status);
+                Error::from(error)
+            }
+            Err(_) => Error::Status(status, String::from_utf8_lossy(body).into_owned()),
+        }
+    }
+
+    /// Fails with [`Error::NoteDeleted`] or [`Error::NoteHidden`] if the content of `note`
+    /// isn't available.
+    pub(crate) fn check_note(note: Note) -> Result<Note> {
+        if note.deleted_at.is_some() {
+            return Err(Error::NoteDeleted(Box::new(note)));
+        }
+        if note.is_hidden == Some(true) {
+            return Err(Error::NoteHidden(Box::new(note)));
+        }
+        Ok(note)
+    }
+
+    /// The Misskey error code, if the server sent one.
+    pub fn code(&self) -> Option<&str> {
+        self.api_error().map(|x| x.code.as_str())
+    }
+
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Error::RateLimited(e)
+            | Error::Unauthorized(e)
+            | Error::NoSuchNote(e)
+            | Error::PermissionDenied(e)
+            | Error::Api(e) => Some(e),
+            Error::NoteDeleted(_)
+            | Error::NoteHidden(_)
+            | Error::ReactionNotAccepted(_)
+            | Error::MissingToken
+            | Error::InvalidUrl(_)
+            | Error::Status(_, _)
+            | Error::Transport(_)
+            | Error::Decode(_)
+            | Error::Io(_) => None,
+        }
+    }
+}
+
+impl From<ApiError> for Error {
+    fn from(error: ApiError) -> Self {
+        match error.code.as_str() {
+            "RATE_LIMIT_EXCEEDED" => Error::RateLimited(Box::new(error)),
+            "CREDENTIAL_REQUIRED" | "AUTHENTICATION_FAILED" => Error::Unauthorized(Box::new(error)),
+            "NO_SUCH_NOTE" => Error::NoSuchNote(Box::new(error)),
+            "PERMISSION_DENIED" | "ROLE_PERMISSION_DENIED" => {
+                Error::PermissionDenied(Box::new(error))
+            }
+            _ => Error::Api(Box::new(error)),
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Transport(err)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Decode(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::RateLimited(e)
+            | Error::Unauthorized(e)
+            | Error::NoSuchNote(e)
+            | Error::PermissionDenied(e)
+            | Error::Api(e) => write!(f, "{e}"),
+            Error::NoteDeleted(note) => write!(f, "note {} has been deleted", note.id),
+            Error::NoteHidden(note) => write!(f, "note {} is hidden", note.id),
+            Error::ReactionNotAccepted(acceptance) => {
+                write!(f, "the note doesn't accept this reaction ({acceptance:?})")
+            }
+            Error::MissingToken => write!(f, "this endpoint requires a token"),
+            Error::InvalidUrl(url) => write!(f, "invalid URL: {url}"),
+            Error::Status(status, body) => write!(f, "unexpected response {status}: {body}"),
+            Error::Transport(err) => write!(f, "transport error: {err}"),
+            Error::Decode(err) => write!(f, "failed to decode response: {err}"),
+            Error::Io(err) => write!(f, "I/O error: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Transport(err) => Some(err),
+            Error::Decode(err) => Some(err),
+            Error::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests;
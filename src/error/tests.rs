@@ -0,0 +1,77 @@
+use super::*;
+use crate::fixtures;
+
+#[test]
+fn no_such_note() {
+    let body = br#"{"error":{"message":"No such note.","code":"NO_SUCH_NOTE","id":"24fcbfc6-2e37-42b6-8388-c29b3861a08d","kind":"client"}}"#;
+    let err = Error::from_response(StatusCode::BAD_REQUEST, body);
+
+    assert!(matches!(err, Error::NoSuchNote(_)));
+    assert_eq!(err.code(), Some("NO_SUCH_NOTE"));
+    assert_eq!(
+        err.api_error().unwrap().status,
+        Some(StatusCode::BAD_REQUEST)
+    );
+}
+
+#[test]
+fn rate_limited() {
+    let body = br#"{"error":{"message":"Rate limit exceeded. Please try again later.","code":"RATE_LIMIT_EXCEEDED","id":"d5826d14-3982-4d2e-8011-b9e9f02499ef","kind":"client","info":{"resetMs":1234}}}"#;
+    let err = Error::from_response(StatusCode::TOO_MANY_REQUESTS, body);
+
+    let Error::RateLimited(api) = err else {
+        panic!("{err:?}");
+    };
+    assert_eq!(api.info, Some(serde_json::json!({"resetMs": 1234})));
+}
+
+#[test]
+fn unknown_code() {
+    let body = br#"{"error":{"message":"Already reacted.","code":"ALREADY_REACTED","id":"71efcf98-86d6-4e2b-b2ad-9d032369366b"}}"#;
+    let err = Error::from_response(StatusCode::BAD_REQUEST, body);
+
+    assert!(matches!(err, Error::Api(_)));
+    assert_eq!(err.code(), Some("ALREADY_REACTED"));
+}
+
+#[test]
+fn not_json() {
+    let err = Error::from_response(StatusCode::BAD_GATEWAY, b"Bad Gateway");
+
+    assert!(
+        matches!(err, Error::Status(StatusCode::BAD_GATEWAY, ref body) if body == "Bad Gateway")
+    );
+    assert_eq!(err.code(), None);
+}
+
+fn note(extra: serde_json::Value) -> Note {
+    serde_json::from_value(fixtures::note(extra)).unwrap()
+}
+
+#[test]
+fn note_deleted() {
+    let deleted = note(serde_json::json!({"deletedAt": "2025-01-02T00:00:00.000Z"}));
+    let err = Error::check_note(deleted).unwrap_err();
+
+    let Error::NoteDeleted(note) = err else {
+        panic!("{err:?}");
+    };
+    assert_eq!(note.id, "9xyz000001");
+}
+
+#[test]
+fn note_hidden() {
+    let hidden = note(serde_json::json!({"isHidden": true}));
+    let err = Error::check_note(hidden).unwrap_err();
+
+    assert!(matches!(err, Error::NoteHidden(_)));
+    assert_eq!(err.code(), None);
+}
+
+#[test]
+fn note_visible() {
+    let visible = note(serde_json::json!({"isHidden": false, "text": "hello"}));
+    let note = Error::check_note(visible).unwrap();
+
+    assert_eq!(note.text.as_deref(), Some("hello"));
+}
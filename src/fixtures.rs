@@ -0,0 +1,47 @@
+//! Entity JSON shared by tests, shaped like Misskey responses.
+
+use serde_json::{json, Value};
+
+/// `UserLite` of `@alice`, id `9abc000001`.
+pub(crate) fn user() -> Value {
+    json!({
+        "id": "9abc000001",
+        "name": null,
+        "username": "alice",
+        "host": null,
+        "avatarUrl": null,
+        "avatarBlurhash": null,
+        "avatarDecorations": [],
+        "emojis": {},
+        "onlineStatus": "unknown"
+    })
+}
+
+/// A public note `9xyz000001` by [`user`] saying `hello`, with the top-level fields of
+/// `extra` replaced or added.
+pub(crate) fn note(extra: Value) -> Value {
+    let mut note = json!({
+        "id": "9xyz000001",
+        "createdAt": "2025-01-01T00:00:00.000Z",
+        "userId": "9abc000001",
+        "user": user(),
+        "text": "hello",
+        "cw": null,
+        "visibility": "public",
+        "localOnly": false,
+        "reactionAcceptance": null,
+        "renoteCount": 0,
+        "repliesCount": 0,
+        "reactionCount": 0,
+        "reactions": {},
+        "reactionEmojis": {},
+        "fileIds": [],
+        "files": [],
+        "replyId": null,
+        "renoteId": null
+    });
+    note.as_object_mut()
+        .unwrap()
+        .extend(extra.as_object().unwrap().clone());
+    note
+}
@@ -1,13 +1,38 @@
 mod common;
 
 pub mod client;
+pub mod drive_upload;
+pub mod endpoints;
+pub mod error;
 pub mod mi_entities;
+pub mod note_draft;
+pub mod note_state;
+pub mod paginator;
+pub mod reaction;
 pub mod receiving_ws_msg;
+pub mod reconnecting_connection;
 pub mod sending_ws_msg;
+pub mod stats_history;
 pub mod ws_connection;
+pub mod ws_mux;
 
-pub use client::Client;
+pub use client::{Client, ClientBuilder};
 pub use common::Real;
+pub use drive_upload::{DriveUpload, UploadProgress};
+pub use endpoints::Endpoint;
+pub use error::{Error, Result};
+pub use note_draft::{NoteDraft, PollDraft};
+pub use note_state::NoteState;
+pub use paginator::{Pagination, Paginator};
+
+#[cfg(feature = "drive-sync")]
+pub mod drive_sync;
 
 #[cfg(feature = "parser")]
 pub mod parser;
+
+#[cfg(test)]
+mod fixtures;
+
+#[cfg(test)]
+mod mock_server;
@@ -2,11 +2,15 @@ use serde::{Deserialize, Serialize};
 
 pub mod achievement;
 pub mod announcement;
+pub mod antenna;
 pub mod channel;
 pub mod drive_file;
+// Empty so far apart from its serde import.
+#[allow(unused_imports)]
 pub mod field;
 pub mod meta;
 pub mod note;
+pub mod notification;
 pub mod page;
 pub mod role_lite;
 pub mod roll_policies;
@@ -14,14 +18,16 @@ pub mod user;
 
 pub use achievement::Achievements;
 pub use announcement::Announcement;
+pub use antenna::Antenna;
 pub use channel::Channel;
 pub use drive_file::DriveFile;
 pub use meta::{MetaDetailed, MetaLite};
 pub use note::Note;
+pub use notification::Notification;
 pub use page::Page;
 pub use role_lite::RoleLite;
 pub use roll_policies::RolePolicies;
-pub use user::{UserDetailedNotMe, UserLite};
+pub use user::{MeDetailed, UserDetailedNotMe, UserLite};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct User {
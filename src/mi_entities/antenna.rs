@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Antenna {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub name: String,
+
+    /// OR of ANDs.
+    pub keywords: Vec<Vec<String>>,
+    pub exclude_keywords: Vec<Vec<String>>,
+
+    pub src: AntennaSrc,
+    pub user_list_id: Option<String>,
+    pub users: Vec<String>,
+    pub case_sensitive: bool,
+
+    #[serde(default)]
+    pub local_only: bool,
+
+    #[serde(default)]
+    pub exclude_bots: bool,
+
+    pub with_replies: bool,
+    pub with_file: bool,
+    pub is_active: bool,
+
+    #[serde(default)]
+    pub has_unread_note: bool,
+
+    #[serde(default)]
+    pub notify: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AntennaSrc {
+    Home,
+    All,
+    Users,
+    List,
+    UsersBlacklist,
+}
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::common::Int;
+use crate::{common::Int, reaction::Reaction};
 
 use super::{DriveFile, UserLite, Visibility};
 
@@ -59,8 +61,9 @@ pub struct Note {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub poll: Option<NotePoll>,
 
+    /// Custom emojis in the text and cw, by name.
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub emojis: Option<std::collections::HashMap<String, serde_json::Value>>, // FIXME
+    pub emojis: Option<HashMap<String, String>>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub channel_id: Option<String>,
@@ -72,8 +75,12 @@ pub struct Note {
     pub local_only: Option<bool>,
 
     pub reaction_acceptance: Option<ReactionAcceptance>,
-    pub reaction_emojis: std::collections::HashMap<String, serde_json::Value>, // FIXME
-    pub reactions: std::collections::HashMap<String, serde_json::Value>,       // FIXME
+
+    /// URLs of remote custom emojis in `reactions`, keyed by `name@host`.
+    pub reaction_emojis: HashMap<String, String>,
+
+    pub reactions: HashMap<Reaction, Int>,
+
     pub reaction_count: Int,
     pub renote_count: Int,
     pub replies_count: Int,
@@ -91,7 +98,31 @@ pub struct Note {
     pub clipped_count: Option<Int>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub my_reaction: Option<String>,
+    pub my_reaction: Option<Reaction>,
+}
+
+impl Note {
+    /// Reactions ordered by count, most used first.
+    pub fn top_reactions(&self) -> Vec<(&Reaction, Int)> {
+        let mut reactions: Vec<_> = self.reactions.iter().map(|(r, n)| (r, *n)).collect();
+        reactions.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        reactions
+    }
+
+    /// Looks up a custom emoji URL by `name` or `name@host`, with or without colons.
+    pub fn resolve_emoji_url(&self, name: &str) -> Option<&str> {
+        let name = name
+            .strip_prefix(':')
+            .and_then(|x| x.strip_suffix(':'))
+            .unwrap_or(name);
+        let name = name.strip_suffix("@.").unwrap_or(name);
+
+        self.emojis
+            .as_ref()
+            .and_then(|x| x.get(name))
+            .or_else(|| self.reaction_emojis.get(name))
+            .map(String::as_str)
+    }
 }
 
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
@@ -105,6 +136,28 @@ pub struct NotePoll {
     pub choices: Vec<PollChoice>,
 }
 
+impl NotePoll {
+    /// Counts a vote for `choice`. Returns `false` if there is no such choice.
+    pub fn apply_vote(&mut self, choice: usize, is_mine: bool) -> bool {
+        let Some(c) = self.choices.get_mut(choice) else {
+            return false;
+        };
+        c.votes += 1;
+        if is_mine {
+            c.is_voted = true;
+        }
+        true
+    }
+
+    pub fn total_votes(&self) -> Int {
+        self.choices.iter().map(|x| x.votes).sum()
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|x| x <= now)
+    }
+}
+
 #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PollChoice {
@@ -132,3 +185,16 @@ pub enum ReactionAcceptance {
     NonSensitiveOnly,
     NonSensitiveOnlyForLocalLikeOnlyForRemote,
 }
+
+/// An element of `notes/reactions`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteReaction {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub user: UserLite,
+    pub r#type: Reaction,
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,50 @@
+use super::*;
+use crate::fixtures;
+
+fn note() -> Note {
+    serde_json::from_value(fixtures::note(serde_json::json!({
+        "text": "hello :wave:",
+        "reactionCount": 6,
+        "reactions": {"👍": 1, ":blobcat@.:": 3, ":blobfox@remote.example:": 2},
+        "reactionEmojis": {"blobfox@remote.example": "https://remote.example/emoji/blobfox.webp"},
+        "emojis": {"wave": "https://misskey.example/emoji/wave.webp"},
+        "myReaction": ":blobcat@.:"
+    })))
+    .unwrap()
+}
+
+#[test]
+fn typed_reactions() {
+    let note = note();
+
+    assert_eq!(note.my_reaction, Some(Reaction::local("blobcat")));
+    assert_eq!(
+        note.top_reactions(),
+        vec![
+            (&Reaction::local("blobcat"), 3),
+            (
+                &Reaction::Custom {
+                    name: "blobfox".to_owned(),
+                    host: Some("remote.example".to_owned()),
+                },
+                2
+            ),
+            (&Reaction::Unicode("👍".to_owned()), 1),
+        ]
+    );
+}
+
+#[test]
+fn resolve_emoji_url() {
+    let note = note();
+
+    assert_eq!(
+        note.resolve_emoji_url("wave"),
+        Some("https://misskey.example/emoji/wave.webp")
+    );
+    assert_eq!(
+        note.resolve_emoji_url(":blobfox@remote.example:"),
+        Some("https://remote.example/emoji/blobfox.webp")
+    );
+    assert_eq!(note.resolve_emoji_url("blobcat"), None);
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::reaction::Reaction;
+
+use super::{Note, RoleLite, UserLite};
+
+/// Fields other than `id`, `createdAt` and `type` are set depending on the type.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub r#type: NotificationType,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<UserLite>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<Box<Note>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reaction: Option<Reaction>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<RoleLite>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub achievement: Option<String>,
+
+    /// `app` notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationType {
+    Note,
+    Follow,
+    Mention,
+    Reply,
+    Renote,
+    Quote,
+    Reaction,
+    PollEnded,
+    ReceiveFollowRequest,
+    FollowRequestAccepted,
+    RoleAssigned,
+    ChatRoomInvitationReceived,
+    AchievementEarned,
+    ExportCompleted,
+    Login,
+    CreateToken,
+    App,
+    Test,
+
+    /// Types added by newer servers.
+    #[serde(other)]
+    Unknown,
+}
@@ -136,6 +136,7 @@ pub struct UserDetailedNotMe {
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct MeOnly {
     pub avatar_id: Option<String>,
     pub banner_id: Option<String>,
@@ -283,3 +284,6 @@ pub struct SecurityKey {
     pub name: String,
     pub last_used: String,
 }
+
+#[cfg(test)]
+mod tests;
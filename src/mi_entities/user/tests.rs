@@ -0,0 +1,97 @@
+use super::*;
+
+/// `MeOnly` part of an `i` response, as sent by Misskey 2025.
+const ME_ONLY: &str = r#"{
+    "avatarId": "9avatar001",
+    "bannerId": null,
+    "followedMessage": null,
+    "isModerator": false,
+    "isAdmin": false,
+    "injectFeaturedNote": true,
+    "receiveAnnouncementEmail": true,
+    "alwaysMarkNsfw": false,
+    "autoSensitive": false,
+    "carefulBot": false,
+    "autoAcceptFollowed": true,
+    "noCrawle": false,
+    "preventAiLearning": true,
+    "isExplorable": true,
+    "isDeleted": false,
+    "twoFactorBackupCodesStock": "none",
+    "hideOnlineStatus": false,
+    "hasUnreadSpecifiedNotes": false,
+    "hasUnreadMentions": true,
+    "hasUnreadAnnouncement": false,
+    "unreadAnnouncements": [],
+    "hasUnreadAntenna": false,
+    "hasUnreadChannel": false,
+    "hasUnreadChatMessages": false,
+    "hasUnreadNotification": true,
+    "hasPendingReceivedFollowRequest": false,
+    "unreadNotificationsCount": 3,
+    "mutedWords": [["foo", "bar"]],
+    "hardMutedWords": [],
+    "mutedInstances": [],
+    "notificationRecieveConfig": {},
+    "emailNotificationTypes": ["follow"],
+    "achievements": [{"name": "notes1", "unlockedAt": 1735689600000}],
+    "loggedInDays": 10,
+    "policies": {
+        "gtlAvailable": true,
+        "ltlAvailable": true,
+        "canPublicNote": true,
+        "mentionLimit": 20,
+        "canInvite": false,
+        "inviteLimit": 0,
+        "inviteLimitCycle": 10080,
+        "inviteExpirationTime": 0,
+        "canManageCustomEmojis": false,
+        "canManageAvatarDecorations": false,
+        "canSearchNotes": true,
+        "canUseTranslator": true,
+        "canHideAds": false,
+        "driveCapacityMb": 1024,
+        "maxFileSizeMb": 30,
+        "alwaysMarkNsfw": false,
+        "canUpdateBioMedia": true,
+        "pinLimit": 5,
+        "antennaLimit": 5,
+        "wordMuteLimit": 200,
+        "webhookLimit": 3,
+        "clipLimit": 10,
+        "noteEachClipsLimit": 200,
+        "userListLimit": 10,
+        "userEachUserListsLimit": 50,
+        "rateLimitFactor": 1,
+        "avatarDecorationLimit": 1,
+        "canImportAntennas": true,
+        "canImportBlocking": true,
+        "canImportFollowing": true,
+        "canImportMuting": true,
+        "canImportUserLists": true,
+        "chatAvailability": "available"
+    },
+    "twoFactorEnabled": false,
+    "usePasswordLessLogin": false,
+    "securityKeys": false,
+    "email": "alice@misskey.example",
+    "emailVerified": true
+}"#;
+
+#[test]
+fn me_only() {
+    let me: MeOnly = serde_json::from_str(ME_ONLY).unwrap();
+
+    assert_eq!(me.avatar_id.as_deref(), Some("9avatar001"));
+    assert_eq!(
+        me.two_factor_backup_codes_stock,
+        TwoFactorBackupCodesStock::None
+    );
+    assert!(me.has_unread_mentions);
+    assert_eq!(me.unread_notifications_count, 3);
+    assert_eq!(me.muted_words, [["foo", "bar"]]);
+    assert_eq!(me.achievements[0].unlocked_at, 1_735_689_600_000);
+    assert_eq!(me.policies.drive_capacity_mb, 1024);
+    assert_eq!(me.email.as_deref(), Some("alice@misskey.example"));
+    assert_eq!(me.security_keys_list, None);
+}
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer};
+
+use crate::mi_entities::{note::ReactionAcceptance, DriveFile, Visibility};
+
+/// Request body of `notes/create`.
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteDraft {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cw: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub visible_user_ids: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renote_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_ids: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll: Option<PollDraft>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_only: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reaction_acceptance: Option<ReactionAcceptance>,
+}
+
+impl NoteDraft {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    pub fn cw(mut self, cw: impl Into<String>) -> Self {
+        self.cw = Some(cw.into());
+        self
+    }
+
+    pub fn visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    /// Adds a recipient. Only meaningful with [`Visibility::Specified`].
+    pub fn visible_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.visible_user_ids.push(user_id.into());
+        self
+    }
+
+    pub fn reply_to(mut self, note_id: impl Into<String>) -> Self {
+        self.reply_id = Some(note_id.into());
+        self
+    }
+
+    /// Quotes the note if `text` is also set, otherwise makes a plain renote.
+    pub fn renote_of(mut self, note_id: impl Into<String>) -> Self {
+        self.renote_id = Some(note_id.into());
+        self
+    }
+
+    pub fn channel(mut self, channel_id: impl Into<String>) -> Self {
+        self.channel_id = Some(channel_id.into());
+        self
+    }
+
+    pub fn file_id(mut self, file_id: impl Into<String>) -> Self {
+        self.file_ids.push(file_id.into());
+        self
+    }
+
+    pub fn attach(self, file: &DriveFile) -> Self {
+        self.file_id(&file.id)
+    }
+
+    pub fn poll(mut self, poll: PollDraft) -> Self {
+        self.poll = Some(poll);
+        self
+    }
+
+    pub fn local_only(mut self, local_only: bool) -> Self {
+        self.local_only = Some(local_only);
+        self
+    }
+
+    pub fn reaction_acceptance(mut self, reaction_acceptance: ReactionAcceptance) -> Self {
+        self.reaction_acceptance = Some(reaction_acceptance);
+        self
+    }
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollDraft {
+    pub choices: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub multiple: bool,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "chrono::serde::ts_milliseconds_option"
+    )]
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// Relative to the creation of the note. Ignored if `expires_at` is set.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_millis"
+    )]
+    pub expired_after: Option<Duration>,
+}
+
+impl PollDraft {
+    pub fn new<I>(choices: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Self {
+            choices: choices.into_iter().map(Into::into).collect(),
+            multiple: false,
+            expires_at: None,
+            expired_after: None,
+        }
+    }
+
+    pub fn multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self {
+        self.expires_at = Some(expires_at);
+        self.expired_after = None;
+        self
+    }
+
+    pub fn expires_after(mut self, duration: Duration) -> Self {
+        self.expired_after = Some(duration);
+        self.expires_at = None;
+        self
+    }
+}
+
+fn serialize_millis<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_u128(duration.as_millis()),
+        None => serializer.serialize_none(),
+    }
+}
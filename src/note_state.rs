@@ -0,0 +1,84 @@
+use crate::{mi_entities::Note, receiving_ws_msg::NoteUpdatedBody};
+
+/// A cached [`Note`] kept current by `noteUpdated` events of its `subNote` subscription.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoteState {
+    note: Note,
+    my_user_id: Option<String>,
+}
+
+impl NoteState {
+    /// `my_user_id` tells which reactions and votes are [`Note::my_reaction`] and
+    /// [`PollChoice::is_voted`](crate::mi_entities::note::PollChoice::is_voted).
+    pub fn new(note: Note, my_user_id: Option<String>) -> Self {
+        Self { note, my_user_id }
+    }
+
+    pub fn note(&self) -> &Note {
+        &self.note
+    }
+
+    pub fn into_note(self) -> Note {
+        self.note
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.note.deleted_at.is_some()
+    }
+
+    /// Applies `event`. Returns `false` if it is for another note or couldn't be applied.
+    pub fn apply(&mut self, event: &NoteUpdatedBody) -> bool {
+        if event.id() != self.note.id {
+            return false;
+        }
+        let note = &mut self.note;
+        let my_user_id = self.my_user_id.as_deref();
+
+        match event {
+            NoteUpdatedBody::NoteUpdatedBodyReacted { body, .. } => {
+                *note.reactions.entry(body.reaction.clone()).or_default() += 1;
+                note.reaction_count += 1;
+                if let Some(emoji) = &body.emoji {
+                    if !emoji.name.ends_with("@.") {
+                        note.reaction_emojis
+                            .insert(emoji.name.clone(), emoji.url.clone());
+                    }
+                }
+                if my_user_id == Some(body.user_id.as_str()) {
+                    note.my_reaction = Some(body.reaction.clone());
+                }
+                true
+            }
+            NoteUpdatedBody::NoteUpdatedBodyUnreacted { body, .. } => {
+                let Some(count) = note.reactions.get_mut(&body.reaction) else {
+                    return false;
+                };
+                *count -= 1;
+                if *count <= 0 {
+                    note.reactions.remove(&body.reaction);
+                }
+                note.reaction_count = (note.reaction_count - 1).max(0);
+                if my_user_id == Some(body.user_id.as_str()) {
+                    note.my_reaction = None;
+                }
+                true
+            }
+            NoteUpdatedBody::NoteUpdatedBodyDeleted { body, .. } => {
+                note.deleted_at = Some(body.deleted_at);
+                true
+            }
+            NoteUpdatedBody::NoteUpdatedBodyPollVoted { body, .. } => match &mut note.poll {
+                Some(poll) => body.apply_to(poll, my_user_id),
+                None => false,
+            },
+            NoteUpdatedBody::NoteUpdatedBodyUpdated { body, .. } => {
+                note.text = body.text.clone();
+                note.cw = body.cw.clone();
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,93 @@
+use super::*;
+use crate::{fixtures, reaction::Reaction};
+
+fn state() -> NoteState {
+    let note = fixtures::note(serde_json::json!({
+        "reactionCount": 1,
+        "reactions": {"👍": 1},
+        "poll": {
+            "multiple": false,
+            "expiresAt": null,
+            "choices": [
+                {"text": "a", "votes": 0, "isVoted": false},
+                {"text": "b", "votes": 0, "isVoted": false}
+            ]
+        }
+    }));
+    NoteState::new(
+        serde_json::from_value(note).unwrap(),
+        Some("9abc000002".to_owned()),
+    )
+}
+
+fn event(json: &str) -> NoteUpdatedBody {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn reactions() {
+    let mut state = state();
+
+    assert!(state.apply(&event(
+        r#"{"id":"9xyz000001","type":"reacted","body":{"reaction":":blobfox@remote.example:","emoji":{"name":"blobfox@remote.example","url":"https://remote.example/blobfox.webp"},"userId":"9abc000002"}}"#
+    )));
+    assert!(state.apply(&event(
+        r#"{"id":"9xyz000001","type":"reacted","body":{"reaction":":blobcat@.:","emoji":{"name":"blobcat@.","url":"https://misskey.example/blobcat.webp"},"userId":"9abc000003"}}"#
+    )));
+    let blobfox = Reaction::Custom {
+        name: "blobfox".to_owned(),
+        host: Some("remote.example".to_owned()),
+    };
+    let note = state.note();
+    assert_eq!(note.reaction_count, 3);
+    assert_eq!(note.reactions[&blobfox], 1);
+    assert_eq!(note.reactions[&Reaction::local("blobcat")], 1);
+    assert_eq!(note.my_reaction, Some(blobfox.clone()));
+    assert_eq!(
+        note.resolve_emoji_url(":blobfox@remote.example:"),
+        Some("https://remote.example/blobfox.webp")
+    );
+    assert!(!note.reaction_emojis.contains_key("blobcat@."));
+
+    assert!(state.apply(&event(
+        r#"{"id":"9xyz000001","type":"unreacted","body":{"reaction":":blobfox@remote.example:","userId":"9abc000002"}}"#
+    )));
+    let note = state.note();
+    assert_eq!(note.reaction_count, 2);
+    assert!(!note.reactions.contains_key(&blobfox));
+    assert_eq!(note.my_reaction, None);
+}
+
+#[test]
+fn poll_and_edit() {
+    let mut state = state();
+
+    assert!(state.apply(&event(
+        r#"{"id":"9xyz000001","type":"pollVoted","body":{"choice":1,"userId":"9abc000002"}}"#
+    )));
+    assert!(state.apply(&event(
+        r#"{"id":"9xyz000001","type":"updated","body":{"cw":"spoiler","text":"edited"}}"#
+    )));
+
+    let note = state.note();
+    let poll = note.poll.as_ref().unwrap();
+    assert_eq!(poll.choices[1].votes, 1);
+    assert!(poll.choices[1].is_voted);
+    assert_eq!(note.text.as_deref(), Some("edited"));
+    assert_eq!(note.cw.as_deref(), Some("spoiler"));
+}
+
+#[test]
+fn deleted() {
+    let mut state = state();
+
+    assert!(!state.apply(&event(
+        r#"{"id":"9xyz000009","type":"deleted","body":{"deletedAt":"2025-01-02T00:00:00.000Z"}}"#
+    )));
+    assert!(!state.is_deleted());
+
+    assert!(state.apply(&event(
+        r#"{"id":"9xyz000001","type":"deleted","body":{"deletedAt":"2025-01-02T00:00:00.000Z"}}"#
+    )));
+    assert!(state.is_deleted());
+}
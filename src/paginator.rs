@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use futures::{stream, Stream};
+use serde::Serialize;
+
+use crate::{
+    common::Int,
+    endpoints::Endpoint,
+    error::Result,
+    mi_entities::{drive_file::DriveFolder, note::NoteReaction, DriveFile, Note},
+    Client,
+};
+
+/// `limit`, `sinceId`, `untilId`, `sinceDate` and `untilDate` of list endpoints.
+#[derive(Clone, Default, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<Int>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until_id: Option<String>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "chrono::serde::ts_milliseconds_option"
+    )]
+    pub since_date: Option<DateTime<Utc>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "chrono::serde::ts_milliseconds_option"
+    )]
+    pub until_date: Option<DateTime<Utc>>,
+}
+
+/// An entity usable as a pagination cursor.
+pub trait PageItem {
+    fn id(&self) -> &str;
+    fn created_at(&self) -> DateTime<Utc>;
+}
+
+impl PageItem for Note {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl PageItem for NoteReaction {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl PageItem for DriveFile {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+impl PageItem for DriveFolder {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+/// An endpoint returning a list that can be walked with [`Pagination`].
+pub trait PaginatedEndpoint: Endpoint {
+    type Item: PageItem;
+
+    /// Whether the endpoint honors `sinceDate` and `untilDate`. If not, [`Cursor::Date`]
+    /// walks by id instead.
+    const DATE_CURSOR: bool = true;
+
+    fn pagination(req: &mut Self::Request) -> &mut Pagination;
+    fn into_items(res: Self::Response) -> Vec<Self::Item>;
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Walks towards older items with `untilId`/`untilDate`.
+    #[default]
+    Older,
+
+    /// Walks towards newer items with `sinceId`/`sinceDate`.
+    Newer,
+}
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub enum Cursor {
+    #[default]
+    Id,
+
+    /// Walks by `createdAt`. Items sharing the boundary millisecond may be skipped.
+    Date,
+}
+
+type StopFn<'a, T> = Box<dyn FnMut(&T) -> bool + Send + 'a>;
+
+/// Walks a [`PaginatedEndpoint`] page by page.
+///
+/// Bounds already set in the request's [`Pagination`] are kept, so e.g. `sinceId` limits
+/// how far an [`Direction::Older`] walk goes. Misskey returns the page nearest to `sinceId`
+/// whenever it is set, so such a far-side bound is checked on the items instead of sent.
+pub struct Paginator<'a, E: PaginatedEndpoint> {
+    client: &'a Client,
+    req: E::Request,
+    direction: Direction,
+    cursor: Cursor,
+    stop: Option<StopFn<'a, E::Item>>,
+}
+
+impl<'a, E: PaginatedEndpoint + 'a> Paginator<'a, E> {
+    pub fn new(client: &'a Client, req: E::Request) -> Self {
+        Self {
+            client,
+            req,
+            direction: Direction::default(),
+            cursor: Cursor::default(),
+            stop: None,
+        }
+    }
+
+    pub fn page_size(mut self, limit: Int) -> Self {
+        E::pagination(&mut self.req).limit = Some(limit);
+        self
+    }
+
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn cursor(mut self, cursor: Cursor) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
+    /// Ends the stream before the first item for which `f` returns `true`.
+    pub fn stop_when(mut self, f: impl FnMut(&E::Item) -> bool + Send + 'a) -> Self {
+        self.stop = Some(Box::new(f));
+        self
+    }
+
+    pub fn into_stream(mut self) -> impl Stream<Item = Result<E::Item>> + 'a {
+        struct State<'a, E: PaginatedEndpoint> {
+            p: Paginator<'a, E>,
+            bound: (Option<String>, Option<DateTime<Utc>>),
+            buf: VecDeque<E::Item>,
+            done: bool,
+        }
+
+        if !E::DATE_CURSOR {
+            self.cursor = Cursor::Id;
+        }
+        let pagination = E::pagination(&mut self.req);
+        let bound = match self.direction {
+            Direction::Older => (pagination.since_id.take(), pagination.since_date.take()),
+            Direction::Newer => (pagination.until_id.take(), pagination.until_date.take()),
+        };
+        let state = State {
+            p: self,
+            bound,
+            buf: VecDeque::new(),
+            done: false,
+        };
+
+        stream::unfold(state, |mut st| async move {
+            loop {
+                if let Some(item) = st.buf.pop_front() {
+                    let (bound_id, bound_date) = &st.bound;
+                    let past_bound = match st.p.direction {
+                        Direction::Older => {
+                            bound_id.as_deref().is_some_and(|x| item.id() <= x)
+                                || bound_date.is_some_and(|x| item.created_at() <= x)
+                        }
+                        Direction::Newer => {
+                            bound_id.as_deref().is_some_and(|x| item.id() >= x)
+                                || bound_date.is_some_and(|x| item.created_at() >= x)
+                        }
+                    };
+                    if past_bound || st.p.stop.as_mut().is_some_and(|f| f(&item)) {
+                        return None;
+                    }
+                    return Some((Ok(item), st));
+                }
+                if st.done {
+                    return None;
+                }
+
+                let mut items = match st.p.client.request::<E>(&st.p.req).await {
+                    Ok(res) => E::into_items(res),
+                    Err(err) => {
+                        st.done = true;
+                        return Some((Err(err), st));
+                    }
+                };
+
+                let limit = E::pagination(&mut st.p.req).limit;
+                if items.is_empty() || limit.is_some_and(|n| (items.len() as Int) < n) {
+                    st.done = true;
+                }
+
+                match (st.p.cursor, st.p.direction) {
+                    (Cursor::Id, Direction::Older) => items.sort_by(|a, b| b.id().cmp(a.id())),
+                    (Cursor::Id, Direction::Newer) => items.sort_by(|a, b| a.id().cmp(b.id())),
+                    (Cursor::Date, Direction::Older) => {
+                        items.sort_by_key(|x| std::cmp::Reverse(x.created_at()))
+                    }
+                    (Cursor::Date, Direction::Newer) => items.sort_by_key(|x| x.created_at()),
+                }
+
+                if let Some(last) = items.last() {
+                    let pagination = E::pagination(&mut st.p.req);
+                    match (st.p.cursor, st.p.direction) {
+                        (Cursor::Id, Direction::Older) => {
+                            pagination.until_id = Some(last.id().to_owned());
+                            pagination.until_date = None;
+                        }
+                        (Cursor::Id, Direction::Newer) => {
+                            pagination.since_id = Some(last.id().to_owned());
+                            pagination.since_date = None;
+                        }
+                        (Cursor::Date, Direction::Older) => {
+                            pagination.until_date = Some(last.created_at());
+                            pagination.until_id = None;
+                        }
+                        (Cursor::Date, Direction::Newer) => {
+                            pagination.since_date = Some(last.created_at());
+                            pagination.since_id = None;
+                        }
+                    }
+                }
+
+                st.buf.extend(items);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests;
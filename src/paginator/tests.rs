@@ -0,0 +1,202 @@
+use chrono::TimeZone;
+use futures::{StreamExt, TryStreamExt};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::*;
+use crate::mock_server::{MockServer, Request, Response};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Item {
+    id: String,
+    created_at: DateTime<Utc>,
+}
+
+impl PageItem for Item {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ListRequest {
+    #[serde(flatten)]
+    pagination: Pagination,
+}
+
+struct List;
+
+impl Endpoint for List {
+    type Request = ListRequest;
+    type Response = Vec<Item>;
+
+    const PATH: &'static str = "list";
+    const REQUIRES_AUTH: bool = false;
+}
+
+impl PaginatedEndpoint for List {
+    type Item = Item;
+
+    fn pagination(req: &mut Self::Request) -> &mut Pagination {
+        &mut req.pagination
+    }
+
+    fn into_items(res: Self::Response) -> Vec<Self::Item> {
+        res
+    }
+}
+
+/// Items `i1` to `i5`, item `n` created at `n` seconds.
+fn items(req: &Request) -> Response {
+    let body = req.json();
+    let limit = body["limit"].as_u64().unwrap_or(10) as usize;
+
+    let mut ns: Vec<i64> = (1..=5)
+        .filter(|n| {
+            let id = format!("i{n}");
+            let ms = n * 1000;
+            body["untilId"].as_str().is_none_or(|x| id.as_str() < x)
+                && body["sinceId"].as_str().is_none_or(|x| id.as_str() > x)
+                && body["untilDate"].as_i64().is_none_or(|x| ms < x)
+                && body["sinceDate"].as_i64().is_none_or(|x| ms > x)
+        })
+        .collect();
+    // Like Misskey, the items nearest to the cursor come back.
+    let since_only = (body.get("sinceId").is_some() || body.get("sinceDate").is_some())
+        && body.get("untilId").is_none()
+        && body.get("untilDate").is_none();
+    if !since_only {
+        ns.reverse();
+    }
+    ns.truncate(limit);
+
+    let items: Vec<_> = ns
+        .into_iter()
+        .map(|n| json!({"id": format!("i{n}"), "createdAt": time(n).to_rfc3339()}))
+        .collect();
+    Response::json(json!(items))
+}
+
+fn time(secs: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(secs, 0).unwrap()
+}
+
+fn ids(items: &[Item]) -> Vec<&str> {
+    items.iter().map(|x| x.id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn older_by_id() {
+    let server = MockServer::start(items).await;
+    let client = server.client();
+
+    let items: Vec<_> = client
+        .paginate::<List>(ListRequest::default())
+        .page_size(2)
+        .into_stream()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(ids(&items), ["i5", "i4", "i3", "i2", "i1"]);
+    assert!(server.requests().iter().all(|x| x.path == "/api/list"));
+    let reqs: Vec<_> = server.requests().iter().map(Request::json).collect();
+    assert_eq!(reqs.len(), 3, "stops after the short page");
+    assert_eq!(reqs[0].get("untilId"), None);
+    assert_eq!(reqs[1]["untilId"], "i4");
+    assert_eq!(reqs[2]["untilId"], "i2");
+}
+
+#[tokio::test]
+async fn newer_by_date() {
+    let server = MockServer::start(items).await;
+    let client = server.client();
+
+    let mut req = ListRequest::default();
+    req.pagination.since_date = Some(time(1));
+    let items: Vec<_> = client
+        .paginate::<List>(req)
+        .page_size(2)
+        .direction(Direction::Newer)
+        .cursor(Cursor::Date)
+        .into_stream()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(ids(&items), ["i2", "i3", "i4", "i5"]);
+    let reqs: Vec<_> = server.requests().iter().map(Request::json).collect();
+    assert_eq!(reqs.len(), 3, "stops after the empty page");
+    assert_eq!(reqs[0]["sinceDate"], 1000);
+    assert_eq!(reqs[1]["sinceDate"], 3000);
+    assert_eq!(reqs[2]["sinceDate"], 5000);
+    assert!(reqs.iter().all(|x| x.get("sinceId").is_none()));
+}
+
+#[tokio::test]
+async fn keeps_bounds() {
+    let server = MockServer::start(items).await;
+    let client = server.client();
+
+    let mut req = ListRequest::default();
+    req.pagination.since_id = Some("i2".to_owned());
+    let items: Vec<_> = client
+        .paginate::<List>(req)
+        .page_size(2)
+        .into_stream()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(ids(&items), ["i5", "i4", "i3"]);
+    let reqs: Vec<_> = server.requests().iter().map(Request::json).collect();
+    assert_eq!(reqs.len(), 2);
+    assert!(reqs.iter().all(|x| x.get("sinceId").is_none()));
+}
+
+#[tokio::test]
+async fn stop_when() {
+    let server = MockServer::start(items).await;
+    let client = server.client();
+
+    let items: Vec<_> = client
+        .paginate::<List>(ListRequest::default())
+        .page_size(2)
+        .stop_when(|x| x.id == "i3")
+        .into_stream()
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(ids(&items), ["i5", "i4"]);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn error_ends_stream() {
+    let server = MockServer::start(|_| {
+        Response::status(
+            500,
+            json!({"error": {"message": "Internal error.", "code": "INTERNAL_ERROR", "id": "x"}}),
+        )
+    })
+    .await;
+    let client = server.client();
+
+    let results: Vec<_> = client
+        .paginate::<List>(ListRequest::default())
+        .into_stream()
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        results[0].as_ref().unwrap_err().code(),
+        Some("INTERNAL_ERROR")
+    );
+}
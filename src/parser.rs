@@ -1,12 +1,14 @@
+use std::{cell::RefCell, collections::HashSet};
+
 use nom::{
     branch::alt,
     bytes::{tag, take_while1},
     character::{
-        char as nom_char,
-        complete::{alphanumeric1, one_of},
+        anychar, char as nom_char,
+        complete::{alphanumeric1, one_of, satisfy},
         none_of,
     },
-    combinator::{map, map_res, not, opt, peek, recognize, verify},
+    combinator::{map, map_res, not, opt, peek, recognize, value, verify},
     error::{ErrorKind, ParseError},
     multi::{many0, many1, many_till, separated_list1},
     sequence::{delimited, pair, preceded, separated_pair, terminated},
@@ -14,27 +16,40 @@ use nom::{
 };
 
 mod input;
+mod mfm_fn;
 mod node;
 mod utils;
 
 use input::Input;
 use utils::LINE_BREAK_PATTERNS;
 
-pub use node::{Node, RawNode};
+pub use mfm_fn::{BorderStyle, FnError, FontFamily, MfmFn};
+pub use node::{MathDisplay, MathRenderer, Node, RawNode};
+
+/// How deep syntaxes can nest before the inner ones are left as text, as `nestLimit` of
+/// mfm-js.
+const NEST_LIMIT: usize = 20;
+
+/// Address of the input, depth, whether in a link label, and opener of a syntax that failed
+/// to parse there during the current [`parse_mfm_raw`].
+type FailureKey = (usize, usize, bool, &'static str);
+
+thread_local! {
+    static FAILURES: RefCell<HashSet<FailureKey>> = RefCell::new(HashSet::new());
+}
 
 fn word_chars1(input: Input) -> IResult<Input, Input> {
     take_while1(|c: char| matches!(c, '0'..='9' | 'A'..='Z' | '_' | 'a'..='z'))
         .parse_complete(input)
 }
 
-fn eol<'a>(input: Input<'a>) -> IResult<Input<'a>, Input<'a>> {
+fn eol(input: Input) -> IResult<Input, Input> {
     if input.s.is_empty() {
         return Ok(input.take_split(0));
     }
 
     for pat in LINE_BREAK_PATTERNS {
         if input.s.starts_with(pat) {
-            dbg!("ok");
             return Ok(input.take_split(pat.len()));
         }
     }
@@ -45,10 +60,10 @@ fn eol<'a>(input: Input<'a>) -> IResult<Input<'a>, Input<'a>> {
     )))
 }
 
-fn parse_domain(input: Input) -> IResult<Input, Vec<&str>> {
+fn parse_domain(input: Input<'_>) -> IResult<Input<'_>, Vec<&str>> {
     let domain_label = verify(
         take_while1::<_, Input, _>(|c: char| matches!(c, '-' | '0'..='9' | 'A'..='Z' | 'a'..='z' )),
-        |s: &Input| !s.s.starts_with('_') && !s.s.ends_with('-'),
+        |s: &Input| !s.s.starts_with('-') && !s.s.ends_with('-'),
     );
 
     map(
@@ -67,6 +82,27 @@ fn parse_domain(input: Input) -> IResult<Input, Vec<&str>> {
     .parse_complete(input)
 }
 
+/// Checks `host` is a domain name, returning its labels. A trailing root `.` gives a last
+/// empty label, as in mentions.
+pub fn parse_host(host: &str) -> Option<Vec<&str>> {
+    let input = Input {
+        s: host,
+        is_line_head: false,
+        prev: None,
+        depth: 0,
+        is_link_label: false,
+    };
+
+    match parse_domain(input) {
+        Ok((rest, labels))
+            if rest.s.is_empty() && host.len() <= 253 && labels.iter().all(|x| x.len() <= 63) =>
+        {
+            Some(labels)
+        }
+        _ => None,
+    }
+}
+
 fn parse_char(input: Input) -> IResult<Input, RawNode> {
     match input.s.chars().next() {
         None => Err(nom::Err::Error(nom::error::Error::from_error_kind(
@@ -110,7 +146,7 @@ fn parse_local_custom_emoji(input: Input) -> IResult<Input, RawNode> {
     .parse_complete(input)
 }
 
-fn hashtag_item(input: Input) -> IResult<Input, &str> {
+fn hashtag_item(input: Input<'_>) -> IResult<Input<'_>, &str> {
     let prohibited_chars = concat!(" \u{3000}\t\r\n", r##".,!?'"#:/[\]【】()「」（）<>"##);
 
     alt((
@@ -141,19 +177,83 @@ fn parse_hashtag(input: Input) -> IResult<Input, RawNode> {
 }
 
 fn parse_small(input: Input) -> IResult<Input, RawNode> {
-    map_res(
-        |input| parse_enclosed_text(input, "<small>", "</small>"),
-        |children| {
-            if !children.is_empty() {
-                Ok(RawNode::Small(Box::new(RawNode::Span(children))))
-            } else {
-                Err(nom::error::Error::new(input, nom::error::ErrorKind::Many1))
-            }
-        },
+    parse_enclosed_node(input, "<small>", "</small>", RawNode::Small)
+}
+
+fn parse_bold_tag(input: Input) -> IResult<Input, RawNode> {
+    parse_enclosed_node(input, "<b>", "</b>", RawNode::Bold)
+}
+
+fn parse_bold_asta(input: Input) -> IResult<Input, RawNode> {
+    parse_enclosed_node(input, "**", "**", RawNode::Bold)
+}
+
+fn parse_bold_under(input: Input) -> IResult<Input, RawNode> {
+    map(
+        |input| parse_word_decoration(input, "__"),
+        |children| RawNode::Bold(Box::new(RawNode::Span(children))),
     )
     .parse_complete(input)
 }
 
+fn parse_italic_tag(input: Input) -> IResult<Input, RawNode> {
+    parse_enclosed_node(input, "<i>", "</i>", RawNode::Italic)
+}
+
+fn parse_italic_asta(input: Input) -> IResult<Input, RawNode> {
+    parse_italic_mark(input, "*")
+}
+
+fn parse_italic_under(input: Input) -> IResult<Input, RawNode> {
+    parse_italic_mark(input, "_")
+}
+
+/// `*` and `_` don't start italic right after an alphanumeric, as in `snake_case_name`.
+fn parse_italic_mark<'a>(input: Input<'a>, mark: &'static str) -> IResult<Input<'a>, RawNode<'a>> {
+    if input.prev.is_some_and(|c| c.is_ascii_alphanumeric()) {
+        return Err(nom::Err::Error(nom::error::Error::from_error_kind(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+    map(
+        |input| parse_word_decoration(input, mark),
+        |children| RawNode::Italic(Box::new(RawNode::Span(children))),
+    )
+    .parse_complete(input)
+}
+
+fn parse_strike_tag(input: Input) -> IResult<Input, RawNode> {
+    parse_enclosed_node(input, "<s>", "</s>", RawNode::Strike)
+}
+
+/// `~~` can't enclose `~` or line breaks.
+fn parse_strike_wave(input: Input) -> IResult<Input, RawNode> {
+    map(
+        delimited(
+            tag("~~"),
+            many1(preceded(not(alt((tag("~"), eol))), parse_nested_item)),
+            tag("~~"),
+        ),
+        |children| RawNode::Strike(Box::new(RawNode::Span(children))),
+    )
+    .parse_complete(input)
+}
+
+fn parse_decoration(input: Input) -> IResult<Input, RawNode> {
+    alt((
+        parse_bold_tag,
+        parse_italic_tag,
+        parse_strike_tag,
+        parse_bold_asta,
+        parse_italic_asta,
+        parse_bold_under,
+        parse_italic_under,
+        parse_strike_wave,
+    ))
+    .parse_complete(input)
+}
+
 fn parse_center<'a>(input: Input<'a>) -> IResult<Input<'a>, RawNode<'a>> {
     if !input.is_line_head {
         return Err(nom::Err::Error(nom::error::Error::from_error_kind(
@@ -207,6 +307,168 @@ fn parse_plain_tag<'a>(input: Input<'a>) -> IResult<Input<'a>, RawNode<'a>> {
     Ok((input, RawNode::PlainTag(content)))
 }
 
+fn parse_inline_code(input: Input) -> IResult<Input, RawNode> {
+    map(
+        delimited(
+            nom_char('`'),
+            recognize(many1(preceded(
+                not(alt((tag("`"), tag("´"), eol))),
+                anychar,
+            ))),
+            nom_char('`'),
+        ),
+        |code| RawNode::InlineCode(code.s),
+    )
+    .parse_complete(input)
+}
+
+fn parse_code_block(input: Input) -> IResult<Input, RawNode> {
+    if !input.is_line_head {
+        return Err(nom::Err::Error(nom::error::Error::from_error_kind(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+
+    let fence = || (eol, tag("```"), peek(eol));
+    map(
+        (
+            tag("```"),
+            recognize(many0(preceded(not(eol), anychar))),
+            eol,
+            recognize(many1(preceded(not(fence()), anychar))),
+            fence(),
+        ),
+        |(_, lang, _, code, _)| {
+            let lang = lang.s.trim();
+            RawNode::CodeBlock {
+                lang: (!lang.is_empty()).then_some(lang),
+                code: code.s,
+            }
+        },
+    )
+    .parse_complete(input)
+}
+
+fn parse_math_inline(input: Input) -> IResult<Input, RawNode> {
+    map(
+        delimited(
+            tag("\\("),
+            recognize(many1(preceded(not(alt((tag("\\)"), eol))), anychar))),
+            tag("\\)"),
+        ),
+        |formula| RawNode::MathInline(formula.s),
+    )
+    .parse_complete(input)
+}
+
+/// Line breaks just inside `\[` and `\]` are not part of the formula.
+fn parse_math_block(input: Input) -> IResult<Input, RawNode> {
+    if !input.is_line_head {
+        return Err(nom::Err::Error(nom::error::Error::from_error_kind(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+
+    map(
+        (
+            tag("\\["),
+            opt(eol),
+            recognize(many1(preceded(not((opt(eol), tag("\\]"))), anychar))),
+            opt(eol),
+            tag("\\]"),
+            peek(eol),
+        ),
+        |(_, _, formula, _, _, _)| RawNode::MathBlock(formula.s),
+    )
+    .parse_complete(input)
+}
+
+/// `key` or `key=value` of a function.
+fn fn_arg(input: Input<'_>) -> IResult<Input<'_>, (&str, Option<&str>)> {
+    pair(
+        map(word_chars1, |x| x.s),
+        opt(preceded(
+            nom_char('='),
+            map(
+                take_while1(|c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')),
+                |x: Input| x.s,
+            ),
+        )),
+    )
+    .parse_complete(input)
+}
+
+/// Runs `f` for the syntax opened by `open` unless it already failed at the same place.
+///
+/// Backtracking otherwise retries nested openers again for every way the outer ones can
+/// fail, which takes exponential time.
+fn remember_failure<'a, T>(
+    input: Input<'a>,
+    open: &'static str,
+    f: impl FnOnce(Input<'a>) -> IResult<Input<'a>, T>,
+) -> IResult<Input<'a>, T> {
+    if !input.s.starts_with(open) {
+        return f(input);
+    }
+    let key = (
+        input.s.as_ptr() as usize,
+        input.depth,
+        input.is_link_label,
+        open,
+    );
+    if FAILURES.with_borrow(|x| x.contains(&key)) {
+        return Err(nom::Err::Error(nom::error::Error::from_error_kind(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+
+    let res = f(input);
+    if let Err(nom::Err::Error(_)) = res {
+        FAILURES.with_borrow_mut(|x| x.insert(key));
+    }
+    res
+}
+
+/// Fails if `input` starts with `open` but `close` doesn't follow, as the syntax can't match
+/// then. Cheaper than finding it out through [`remember_failure`].
+fn expect_closer<'a>(input: Input<'a>, open: &str, close: &str) -> IResult<Input<'a>, ()> {
+    match input.s.strip_prefix(open) {
+        Some(rest) if !rest.contains(close) => Err(nom::Err::Error(
+            nom::error::Error::from_error_kind(input, ErrorKind::Tag),
+        )),
+        _ => Ok((input, ())),
+    }
+}
+
+/// `$[name.args children]`. A later duplicate arg wins.
+fn parse_fn(input: Input) -> IResult<Input, RawNode> {
+    expect_closer(input, "$[", "]")?;
+    remember_failure(input, "$[", |input| {
+        map(
+            (
+                tag("$["),
+                word_chars1,
+                opt(preceded(
+                    nom_char('.'),
+                    separated_list1(nom_char(','), fn_arg),
+                )),
+                nom_char(' '),
+                many1(preceded(not(tag("]")), parse_nested_item)),
+                tag("]"),
+            ),
+            |(_, name, args, _, children, _)| RawNode::Fn {
+                name: name.s,
+                args: args.into_iter().flatten().collect(),
+                children: Box::new(RawNode::Span(children)),
+            },
+        )
+        .parse_complete(input)
+    })
+}
+
 fn parse_quote(input: Input) -> IResult<Input, RawNode> {
     if !input.is_line_head {
         return Err(nom::Err::Error(nom::error::Error::from_error_kind(
@@ -228,33 +490,206 @@ fn parse_quote(input: Input) -> IResult<Input, RawNode> {
     .parse(input)
 }
 
-fn parse_span_item(input: Input) -> IResult<Input, RawNode> {
+fn is_url_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || ".,_/:%#@$&?!~=+-".contains(c)
+}
+
+/// A URL char, or balanced `()` or `[]` around URL chars.
+fn url_item(input: Input) -> IResult<Input, Input> {
+    if input.depth < NEST_LIMIT {
+        let depth = input.depth;
+        let nested = alt((
+            recognize((nom_char('('), many0(url_item), nom_char(')'))),
+            recognize((nom_char('['), many0(url_item), nom_char(']'))),
+        ))
+        .parse_complete(Input {
+            depth: depth + 1,
+            ..input
+        });
+        if let Ok((rest, item)) = nested {
+            return Ok((Input { depth, ..rest }, Input { depth, ..item }));
+        }
+    }
+
+    recognize(satisfy(is_url_char)).parse_complete(input)
+}
+
+/// Trailing `.` and `,` are left out, as they usually end the sentence.
+fn url_plain(input: Input) -> IResult<Input, Input> {
+    let (_, (scheme, url)) = (
+        alt((tag("https://"), tag("http://"))),
+        recognize(many1(url_item)),
+    )
+        .parse_complete(input)?;
+
+    let len = scheme.s.len() + url.s.trim_end_matches(['.', ',']).len();
+    if len == scheme.s.len() {
+        return Err(nom::Err::Error(nom::error::Error::from_error_kind(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+
+    Ok(input.take_split(len))
+}
+
+/// `<https://...>`, which may contain any char but spaces.
+fn url_bracketed(input: Input) -> IResult<Input, Input> {
+    delimited(
+        nom_char('<'),
+        recognize((
+            alt((tag("https://"), tag("http://"))),
+            many1(preceded(not(one_of(" \u{3000}\t>")), anychar)),
+        )),
+        nom_char('>'),
+    )
+    .parse_complete(input)
+}
+
+fn parse_url(input: Input) -> IResult<Input, RawNode> {
     alt((
+        map(url_bracketed, |url| RawNode::Url {
+            url: url.s,
+            brackets: true,
+        }),
+        map(url_plain, |url| RawNode::Url {
+            url: url.s,
+            brackets: false,
+        }),
+    ))
+    .parse_complete(input)
+}
+
+/// `[label](url)`, or `?[label](url)` to hide the preview.
+fn parse_link(input: Input) -> IResult<Input, RawNode> {
+    let (input, silent) =
+        alt((value(true, tag("?[")), value(false, tag("[")))).parse_complete(input)?;
+
+    let (rest, children) = many1(preceded(not(alt((tag("]"), eol))), parse_nested_item))
+        .parse_complete(Input {
+            is_link_label: true,
+            ..input
+        })?;
+
+    let (rest, url) = delimited(tag("]("), alt((url_bracketed, url_plain)), nom_char(')'))
+        .parse_complete(Input {
+            is_link_label: false,
+            ..rest
+        })?;
+
+    Ok((
+        rest,
+        RawNode::Link {
+            silent,
+            url: url.s,
+            children: Box::new(RawNode::Span(children)),
+        },
+    ))
+}
+
+/// URLs, links, mentions and hashtags, which are text in link labels.
+fn parse_reference(input: Input) -> IResult<Input, RawNode> {
+    if input.is_link_label {
+        return Err(nom::Err::Error(nom::error::Error::from_error_kind(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+
+    alt((
+        parse_url,
+        parse_link,
         parse_global_user,
         parse_local_user,
-        parse_local_custom_emoji,
         parse_hashtag,
+    ))
+    .parse_complete(input)
+}
+
+fn parse_span_item(input: Input) -> IResult<Input, RawNode> {
+    alt((
+        parse_code_block,
+        parse_inline_code,
+        parse_math_block,
+        parse_math_inline,
+        parse_reference,
+        parse_local_custom_emoji,
         parse_small,
+        parse_decoration,
         parse_center,
         parse_plain_tag,
+        parse_fn,
         parse_quote,
         parse_char,
     ))
     .parse_complete(input)
 }
 
+/// Parses a span item inside another syntax, as `nest` of mfm-js.
+fn parse_nested_item(input: Input) -> IResult<Input, RawNode> {
+    if input.depth >= NEST_LIMIT {
+        return parse_char(input);
+    }
+
+    let depth = input.depth;
+    let (rest, node) = parse_span_item(Input {
+        depth: depth + 1,
+        ..input
+    })?;
+    Ok((Input { depth, ..rest }, node))
+}
+
 fn parse_text(input: Input) -> IResult<Input, RawNode> {
     map(many0(parse_span_item), RawNode::Span).parse_complete(input)
 }
 
-fn parse_enclosed_text<'i, 't: 'i>(
-    input: Input<'i>,
-    open: &'t str,
-    close: &'t str,
-) -> IResult<Input<'i>, Vec<RawNode<'i>>> {
-    preceded(
-        tag(open),
-        map(many_till(parse_span_item, tag(close)), |(nodes, _)| nodes),
+fn parse_enclosed_text<'a>(
+    input: Input<'a>,
+    open: &'static str,
+    close: &'static str,
+) -> IResult<Input<'a>, Vec<RawNode<'a>>> {
+    expect_closer(input, open, close)?;
+    remember_failure(input, open, |input| {
+        preceded(
+            tag(open),
+            map(many_till(parse_nested_item, tag(close)), |(nodes, _)| nodes),
+        )
+        .parse_complete(input)
+    })
+}
+
+/// Parses `open`, one or more span items and `close` into `f`.
+fn parse_enclosed_node<'a>(
+    input: Input<'a>,
+    open: &'static str,
+    close: &'static str,
+    f: fn(Box<RawNode<'a>>) -> RawNode<'a>,
+) -> IResult<Input<'a>, RawNode<'a>> {
+    map_res(
+        |input| parse_enclosed_text(input, open, close),
+        |children| {
+            if !children.is_empty() {
+                Ok(f(Box::new(RawNode::Span(children))))
+            } else {
+                Err(nom::error::Error::new(input, nom::error::ErrorKind::Many1))
+            }
+        },
+    )
+    .parse_complete(input)
+}
+
+/// Parses `mark`, alphanumerics and spaces, and `mark`. Nothing else is allowed inside.
+fn parse_word_decoration<'a>(
+    input: Input<'a>,
+    mark: &'static str,
+) -> IResult<Input<'a>, Vec<RawNode<'a>>> {
+    delimited(
+        tag(mark),
+        many1(map(
+            satisfy(|c: char| c.is_ascii_alphanumeric() || matches!(c, ' ' | '\u{3000}' | '\t')),
+            RawNode::Char,
+        )),
+        tag(mark),
     )
     .parse_complete(input)
 }
@@ -263,11 +698,18 @@ pub fn parse_mfm(input: &str) -> Node {
     parse_mfm_raw(input).into()
 }
 
-pub fn parse_mfm_raw(input: &str) -> RawNode {
-    match parse_text(Input {
+pub fn parse_mfm_raw(input: &str) -> RawNode<'_> {
+    FAILURES.with_borrow_mut(HashSet::clear);
+    let res = parse_text(Input {
         s: input,
         is_line_head: true,
-    }) {
+        prev: None,
+        depth: 0,
+        is_link_label: false,
+    });
+    FAILURES.with_borrow_mut(HashSet::clear);
+
+    match res {
         Ok((x, node)) if x.s.is_empty() => node,
         _ => unreachable!(),
     }
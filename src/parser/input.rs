@@ -6,6 +6,15 @@ use super::utils::LINE_BREAK_PATTERNS;
 pub struct Input<'a> {
     pub s: &'a str,
     pub is_line_head: bool,
+
+    /// The char just before `s` in the whole text.
+    pub prev: Option<char>,
+
+    /// How many nested syntaxes `s` is in.
+    pub depth: usize,
+
+    /// Whether `s` is in the label of a link, where mentions, hashtags and links are text.
+    pub is_link_label: bool,
 }
 
 impl<'a> nom::Input for Input<'a> {
@@ -20,7 +29,7 @@ impl<'a> nom::Input for Input<'a> {
     fn take(&self, index: usize) -> Self {
         Self {
             s: &self.s[..index],
-            is_line_head: self.is_line_head,
+            ..*self
         }
     }
 
@@ -28,6 +37,8 @@ impl<'a> nom::Input for Input<'a> {
         Self {
             s: &self.s[index..],
             is_line_head: is_line_end(&self.s[..index]).unwrap_or(self.is_line_head),
+            prev: self.s[..index].chars().next_back().or(self.prev),
+            ..*self
         }
     }
 
@@ -38,11 +49,10 @@ impl<'a> nom::Input for Input<'a> {
             Input {
                 s: suffix,
                 is_line_head: is_line_end(prefix).unwrap_or(self.is_line_head),
+                prev: prefix.chars().next_back().or(self.prev),
+                ..*self
             },
-            Input {
-                s: prefix,
-                is_line_head: self.is_line_head,
-            },
+            Input { s: prefix, ..*self },
         )
     }
 
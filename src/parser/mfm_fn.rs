@@ -0,0 +1,278 @@
+use std::{collections::BTreeMap, fmt};
+
+use super::Node;
+
+/// Typed view of a [`Node::Fn`] with validated arguments.
+///
+/// Only the functions below are covered. Unknown arguments are ignored like Misskey does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MfmFn {
+    /// `$[flip.h,v ...]`. Flips horizontally if neither is given.
+    Flip {
+        h: bool,
+        v: bool,
+    },
+    X2,
+    X3,
+    X4,
+    Blur,
+
+    /// `$[rainbow.speed=1s,delay=0.5s ...]`, in seconds. Either may be negative.
+    Rainbow {
+        speed: Option<f64>,
+        delay: Option<f64>,
+    },
+    Sparkle,
+
+    /// `$[rotate.deg=45 ...]`. `deg` defaults to 90.
+    Rotate {
+        deg: f64,
+    },
+
+    /// `$[position.x=1,y=-1 ...]`, in em. Each defaults to 0.
+    Position {
+        x: f64,
+        y: f64,
+    },
+
+    /// `$[scale.x=2,y=0.5 ...]`. Each defaults to 1. Misskey clamps them to 5 when rendering.
+    Scale {
+        x: f64,
+        y: f64,
+    },
+
+    /// `$[fg.color=f00 ...]`. `color` is 3 to 6 hex digits without `#`, and defaults to `f00`.
+    Fg {
+        color: String,
+    },
+
+    /// `$[bg.color=0f0 ...]`. `color` defaults to `0f0`.
+    Bg {
+        color: String,
+    },
+
+    /// `$[border.style=dashed,width=2,color=f00,radius=4,noclip ...]`.
+    Border {
+        style: BorderStyle,
+        width: f64,
+        color: Option<String>,
+        radius: f64,
+        noclip: bool,
+    },
+    Font(FontFamily),
+
+    /// `$[ruby base reading]`
+    Ruby,
+
+    /// `$[unixtime 1700000000]`
+    Unixtime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BorderStyle {
+    Hidden,
+    Dotted,
+    Dashed,
+    #[default]
+    Solid,
+    Double,
+    Groove,
+    Ridge,
+    Inset,
+    Outset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FontFamily {
+    Serif,
+    Monospace,
+    Cursive,
+    Fantasy,
+    Emoji,
+    Math,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FnError {
+    /// Not one of the functions of [`MfmFn`].
+    Unknown(String),
+
+    /// A required argument is absent, such as the family of `font`.
+    MissingArg(&'static str),
+
+    /// The argument is a flag where a value is needed, or the value is malformed.
+    InvalidArg { key: String, value: Option<String> },
+}
+
+impl MfmFn {
+    /// Validates `args` of the function `name`, which is case-sensitive like Misskey.
+    pub fn new(name: &str, args: &BTreeMap<String, Option<String>>) -> Result<Self, FnError> {
+        let f = match name {
+            "flip" => {
+                let (h, v) = (args.contains_key("h"), args.contains_key("v"));
+                MfmFn::Flip { h: h || !v, v }
+            }
+            "x2" => MfmFn::X2,
+            "x3" => MfmFn::X3,
+            "x4" => MfmFn::X4,
+            "blur" => MfmFn::Blur,
+            "rainbow" => MfmFn::Rainbow {
+                speed: time_arg(args, "speed")?,
+                delay: time_arg(args, "delay")?,
+            },
+            "sparkle" => MfmFn::Sparkle,
+            "rotate" => MfmFn::Rotate {
+                deg: number_arg(args, "deg")?.unwrap_or(90.0),
+            },
+            "position" => MfmFn::Position {
+                x: number_arg(args, "x")?.unwrap_or(0.0),
+                y: number_arg(args, "y")?.unwrap_or(0.0),
+            },
+            "scale" => MfmFn::Scale {
+                x: number_arg(args, "x")?.unwrap_or(1.0),
+                y: number_arg(args, "y")?.unwrap_or(1.0),
+            },
+            "fg" => MfmFn::Fg {
+                color: color_arg(args, "color")?.unwrap_or_else(|| "f00".to_owned()),
+            },
+            "bg" => MfmFn::Bg {
+                color: color_arg(args, "color")?.unwrap_or_else(|| "0f0".to_owned()),
+            },
+            "border" => MfmFn::Border {
+                style: match value_arg(args, "style")? {
+                    None => BorderStyle::default(),
+                    Some(style) => parse_border_style(style)
+                        .ok_or_else(|| invalid_arg("style", Some(style)))?,
+                },
+                width: number_arg(args, "width")?.unwrap_or(1.0),
+                color: color_arg(args, "color")?,
+                radius: number_arg(args, "radius")?.unwrap_or(0.0),
+                noclip: args.contains_key("noclip"),
+            },
+            "font" => {
+                // Misskey checks the families in this order, whatever order they're written in.
+                let family = FONT_FAMILIES
+                    .into_iter()
+                    .find(|(key, _)| args.contains_key(*key))
+                    .ok_or(FnError::MissingArg("family"))?;
+                MfmFn::Font(family.1)
+            }
+            "ruby" => MfmFn::Ruby,
+            "unixtime" => MfmFn::Unixtime,
+            _ => return Err(FnError::Unknown(name.to_owned())),
+        };
+        Ok(f)
+    }
+}
+
+impl Node {
+    /// Typed view if this is a [`Node::Fn`].
+    pub fn mfm_fn(&self) -> Option<Result<MfmFn, FnError>> {
+        match self {
+            Node::Fn { name, args, .. } => Some(MfmFn::new(name, args)),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_arg(key: &str, value: Option<&str>) -> FnError {
+    FnError::InvalidArg {
+        key: key.to_owned(),
+        value: value.map(str::to_owned),
+    }
+}
+
+/// `Ok(None)` if absent, `Err` if given as a flag.
+fn value_arg<'a>(
+    args: &'a BTreeMap<String, Option<String>>,
+    key: &str,
+) -> Result<Option<&'a str>, FnError> {
+    match args.get(key) {
+        None => Ok(None),
+        Some(Some(value)) => Ok(Some(value)),
+        Some(None) => Err(invalid_arg(key, None)),
+    }
+}
+
+fn number_arg(args: &BTreeMap<String, Option<String>>, key: &str) -> Result<Option<f64>, FnError> {
+    let Some(value) = value_arg(args, key)? else {
+        return Ok(None);
+    };
+    match value.parse::<f64>() {
+        Ok(x) if x.is_finite() => Ok(Some(x)),
+        _ => Err(invalid_arg(key, Some(value))),
+    }
+}
+
+/// Seconds written as `1.5s` or `-1.5s`.
+fn time_arg(args: &BTreeMap<String, Option<String>>, key: &str) -> Result<Option<f64>, FnError> {
+    let Some(value) = value_arg(args, key)? else {
+        return Ok(None);
+    };
+    let seconds = value
+        .strip_suffix('s')
+        .filter(|x| {
+            let digits = x.strip_prefix('-').unwrap_or(x);
+            !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+        })
+        .and_then(|x| x.parse::<f64>().ok());
+    match seconds {
+        Some(x) => Ok(Some(x)),
+        None => Err(invalid_arg(key, Some(value))),
+    }
+}
+
+fn color_arg(
+    args: &BTreeMap<String, Option<String>>,
+    key: &str,
+) -> Result<Option<String>, FnError> {
+    let Some(value) = value_arg(args, key)? else {
+        return Ok(None);
+    };
+    if (3..=6).contains(&value.len()) && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(Some(value.to_owned()))
+    } else {
+        Err(invalid_arg(key, Some(value)))
+    }
+}
+
+fn parse_border_style(s: &str) -> Option<BorderStyle> {
+    let style = match s {
+        "hidden" => BorderStyle::Hidden,
+        "dotted" => BorderStyle::Dotted,
+        "dashed" => BorderStyle::Dashed,
+        "solid" => BorderStyle::Solid,
+        "double" => BorderStyle::Double,
+        "groove" => BorderStyle::Groove,
+        "ridge" => BorderStyle::Ridge,
+        "inset" => BorderStyle::Inset,
+        "outset" => BorderStyle::Outset,
+        _ => return None,
+    };
+    Some(style)
+}
+
+const FONT_FAMILIES: [(&str, FontFamily); 6] = [
+    ("serif", FontFamily::Serif),
+    ("monospace", FontFamily::Monospace),
+    ("cursive", FontFamily::Cursive),
+    ("fantasy", FontFamily::Fantasy),
+    ("emoji", FontFamily::Emoji),
+    ("math", FontFamily::Math),
+];
+
+impl fmt::Display for FnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FnError::Unknown(name) => write!(f, "unknown function: {name}"),
+            FnError::MissingArg(key) => write!(f, "missing argument: {key}"),
+            FnError::InvalidArg { key, value: None } => write!(f, "argument needs a value: {key}"),
+            FnError::InvalidArg {
+                key,
+                value: Some(value),
+            } => write!(f, "invalid argument: {key}={value}"),
+        }
+    }
+}
+
+impl std::error::Error for FnError {}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum RawNode<'a> {
     Span(Vec<RawNode<'a>>),
@@ -6,8 +8,34 @@ pub enum RawNode<'a> {
     LocalCustomEmoji(&'a str),
     HashTag(&'a str),
     Small(Box<RawNode<'a>>),
+    Bold(Box<RawNode<'a>>),
+    Italic(Box<RawNode<'a>>),
+    Strike(Box<RawNode<'a>>),
     Center(Box<RawNode<'a>>),
     PlainTag(&'a str),
+    InlineCode(&'a str),
+    CodeBlock {
+        lang: Option<&'a str>,
+        code: &'a str,
+    },
+    MathInline(&'a str),
+    MathBlock(&'a str),
+
+    /// `brackets` if written as `<https://...>`.
+    Url {
+        url: &'a str,
+        brackets: bool,
+    },
+    Link {
+        silent: bool,
+        url: &'a str,
+        children: Box<RawNode<'a>>,
+    },
+    Fn {
+        name: &'a str,
+        args: BTreeMap<&'a str, Option<&'a str>>,
+        children: Box<RawNode<'a>>,
+    },
     Quote(usize, Box<RawNode<'a>>),
     Char(char),
 }
@@ -21,8 +49,46 @@ pub enum Node {
     LocalCustomEmoji(String),
     HashTag(String),
     Small(Box<Node>),
+    Bold(Box<Node>),
+    Italic(Box<Node>),
+    Strike(Box<Node>),
     Center(Box<Node>),
     PlainTag(String),
+    InlineCode(String),
+    CodeBlock {
+        lang: Option<String>,
+        code: String,
+    },
+
+    /// `rendered` is set by [`Node::render_math`].
+    MathInline {
+        formula: String,
+        rendered: Option<String>,
+    },
+    MathBlock {
+        formula: String,
+        rendered: Option<String>,
+    },
+
+    /// `brackets` if written as `<https://...>`.
+    Url {
+        url: String,
+        brackets: bool,
+    },
+
+    /// `silent` if written as `?[label](url)`, which shouldn't be previewed.
+    Link {
+        silent: bool,
+        url: String,
+        children: Box<Node>,
+    },
+
+    /// `$[name.key=value,flag children]`. See [`MfmFn`](super::MfmFn) for known functions.
+    Fn {
+        name: String,
+        args: BTreeMap<String, Option<String>>,
+        children: Box<Node>,
+    },
     Quote(usize, Box<Node>),
     Plain(String),
 }
@@ -48,13 +114,39 @@ impl RawNode<'_> {
                 }
             }
             RawNode::Small(child) => RawNode::Small(Box::new(child.flatten())),
+            RawNode::Bold(child) => RawNode::Bold(Box::new(child.flatten())),
+            RawNode::Italic(child) => RawNode::Italic(Box::new(child.flatten())),
+            RawNode::Strike(child) => RawNode::Strike(Box::new(child.flatten())),
             RawNode::Center(child) => RawNode::Center(Box::new(child.flatten())),
             RawNode::Quote(n, child) => RawNode::Quote(n, Box::new(child.flatten())),
+            RawNode::Link {
+                silent,
+                url,
+                children,
+            } => RawNode::Link {
+                silent,
+                url,
+                children: Box::new(children.flatten()),
+            },
+            RawNode::Fn {
+                name,
+                args,
+                children,
+            } => RawNode::Fn {
+                name,
+                args,
+                children: Box::new(children.flatten()),
+            },
             RawNode::GlobalUser(_, _)
             | RawNode::LocalUser(_)
             | RawNode::LocalCustomEmoji(_)
             | RawNode::HashTag(_)
             | RawNode::PlainTag(_)
+            | RawNode::InlineCode(_)
+            | RawNode::CodeBlock { .. }
+            | RawNode::MathInline(_)
+            | RawNode::MathBlock(_)
+            | RawNode::Url { .. }
             | RawNode::Char(_) => self,
         }
     }
@@ -86,8 +178,49 @@ impl RawNode<'_> {
             RawNode::LocalCustomEmoji(name) => Node::LocalCustomEmoji(name.to_owned()),
             RawNode::HashTag(name) => Node::HashTag(name.to_owned()),
             RawNode::Small(child) => Node::Small(Box::new(child.into_node())),
+            RawNode::Bold(child) => Node::Bold(Box::new(child.into_node())),
+            RawNode::Italic(child) => Node::Italic(Box::new(child.into_node())),
+            RawNode::Strike(child) => Node::Strike(Box::new(child.into_node())),
             RawNode::Center(child) => Node::Center(Box::new(child.into_node())),
             RawNode::PlainTag(s) => Node::PlainTag(s.to_owned()),
+            RawNode::InlineCode(code) => Node::InlineCode(code.to_owned()),
+            RawNode::CodeBlock { lang, code } => Node::CodeBlock {
+                lang: lang.map(str::to_owned),
+                code: code.to_owned(),
+            },
+            RawNode::MathInline(formula) => Node::MathInline {
+                formula: formula.to_owned(),
+                rendered: None,
+            },
+            RawNode::MathBlock(formula) => Node::MathBlock {
+                formula: formula.to_owned(),
+                rendered: None,
+            },
+            RawNode::Url { url, brackets } => Node::Url {
+                url: url.to_owned(),
+                brackets,
+            },
+            RawNode::Link {
+                silent,
+                url,
+                children,
+            } => Node::Link {
+                silent,
+                url: url.to_owned(),
+                children: Box::new(children.into_node()),
+            },
+            RawNode::Fn {
+                name,
+                args,
+                children,
+            } => Node::Fn {
+                name: name.to_owned(),
+                args: args
+                    .into_iter()
+                    .map(|(k, v)| (k.to_owned(), v.map(str::to_owned)))
+                    .collect(),
+                children: Box::new(children.into_node()),
+            },
             RawNode::Quote(n, child) => Node::Quote(n, Box::new(child.into_node())),
             RawNode::Char(c) => Node::Plain(c.to_string()),
         }
@@ -105,18 +238,110 @@ impl Node {
             ),
             Node::LocalUser(name) => Node::GlobalUser(name, source_host.clone()),
             Node::Small(child) => Node::Small(Box::new(child.into_global(source_host.clone()))),
+            Node::Bold(child) => Node::Bold(Box::new(child.into_global(source_host.clone()))),
+            Node::Italic(child) => Node::Italic(Box::new(child.into_global(source_host.clone()))),
+            Node::Strike(child) => Node::Strike(Box::new(child.into_global(source_host.clone()))),
             Node::Center(child) => Node::Center(Box::new(child.into_global(source_host.clone()))),
             Node::Quote(n, child) => {
                 Node::Quote(n, Box::new(child.into_global(source_host.clone())))
             }
+            Node::Fn {
+                name,
+                args,
+                children,
+            } => Node::Fn {
+                name,
+                args,
+                children: Box::new(children.into_global(source_host.clone())),
+            },
             Node::Empty
             | Node::GlobalUser(_, _)
             | Node::LocalCustomEmoji(_)
             | Node::HashTag(_)
             | Node::PlainTag(_)
+            | Node::InlineCode(_)
+            | Node::CodeBlock { .. }
+            | Node::MathInline { .. }
+            | Node::MathBlock { .. }
+            | Node::Url { .. }
+            | Node::Link { .. }
             | Node::Plain(_) => self,
         }
     }
+
+    /// Typesets every formula with `renderer`.
+    pub fn render_math(self, renderer: &impl MathRenderer) -> Self {
+        match self {
+            Node::Span(nodes) => {
+                Node::Span(nodes.into_iter().map(|x| x.render_math(renderer)).collect())
+            }
+            Node::Small(child) => Node::Small(Box::new(child.render_math(renderer))),
+            Node::Bold(child) => Node::Bold(Box::new(child.render_math(renderer))),
+            Node::Italic(child) => Node::Italic(Box::new(child.render_math(renderer))),
+            Node::Strike(child) => Node::Strike(Box::new(child.render_math(renderer))),
+            Node::Center(child) => Node::Center(Box::new(child.render_math(renderer))),
+            Node::Quote(n, child) => Node::Quote(n, Box::new(child.render_math(renderer))),
+            Node::Link {
+                silent,
+                url,
+                children,
+            } => Node::Link {
+                silent,
+                url,
+                children: Box::new(children.render_math(renderer)),
+            },
+            Node::Fn {
+                name,
+                args,
+                children,
+            } => Node::Fn {
+                name,
+                args,
+                children: Box::new(children.render_math(renderer)),
+            },
+            Node::MathInline { formula, .. } => Node::MathInline {
+                rendered: renderer.render(&formula, MathDisplay::Inline),
+                formula,
+            },
+            Node::MathBlock { formula, .. } => Node::MathBlock {
+                rendered: renderer.render(&formula, MathDisplay::Block),
+                formula,
+            },
+            Node::Empty
+            | Node::GlobalUser(_, _)
+            | Node::LocalUser(_)
+            | Node::LocalCustomEmoji(_)
+            | Node::HashTag(_)
+            | Node::PlainTag(_)
+            | Node::InlineCode(_)
+            | Node::CodeBlock { .. }
+            | Node::Url { .. }
+            | Node::Plain(_) => self,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MathDisplay {
+    /// `\(...\)`
+    Inline,
+
+    /// `\[...\]`
+    Block,
+}
+
+/// Typesets formulas for [`Node::render_math`], e.g. into MathML or SVG.
+///
+/// Implemented for closures taking the formula and its [`MathDisplay`].
+pub trait MathRenderer {
+    /// Returns `None` to leave the formula unrendered, e.g. on a syntax error.
+    fn render(&self, formula: &str, display: MathDisplay) -> Option<String>;
+}
+
+impl<F: Fn(&str, MathDisplay) -> Option<String>> MathRenderer for F {
+    fn render(&self, formula: &str, display: MathDisplay) -> Option<String> {
+        self(formula, display)
+    }
 }
 
 impl<'a> From<RawNode<'a>> for Node {
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use super::*;
 
 #[test]
@@ -435,3 +437,610 @@ fn plain_tag1() {
     let node = parse_mfm("<plain>aaa</plain>");
     assert_eq!(node, Node::PlainTag("aaa".to_owned()));
 }
+
+#[test]
+fn bold1() {
+    assert_eq!(
+        parse_mfm("**abc**"),
+        Node::Bold(Box::new(Node::Plain("abc".to_owned())))
+    );
+    assert_eq!(
+        parse_mfm("<b>abc</b>"),
+        Node::Bold(Box::new(Node::Plain("abc".to_owned())))
+    );
+    assert_eq!(
+        parse_mfm("__abc__"),
+        Node::Bold(Box::new(Node::Plain("abc".to_owned())))
+    );
+}
+
+#[test]
+fn bold2() {
+    let node = parse_mfm("before **@alice :smile:** after");
+    assert_eq!(
+        node,
+        Node::Span(vec![
+            Node::Plain("before ".to_owned()),
+            Node::Bold(Box::new(Node::Span(vec![
+                Node::LocalUser("alice".to_owned()),
+                Node::Plain(" ".to_owned()),
+                Node::LocalCustomEmoji("smile".to_owned()),
+            ]))),
+            Node::Plain(" after".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn bold3() {
+    // `__` only encloses alphanumerics and spaces.
+    assert_eq!(parse_mfm("__a-b__"), Node::Plain("__a-b__".to_owned()));
+    assert_eq!(parse_mfm("****"), Node::Plain("****".to_owned()));
+}
+
+#[test]
+fn italic1() {
+    assert_eq!(
+        parse_mfm("*abc*"),
+        Node::Italic(Box::new(Node::Plain("abc".to_owned())))
+    );
+    assert_eq!(
+        parse_mfm("_abc_"),
+        Node::Italic(Box::new(Node::Plain("abc".to_owned())))
+    );
+    assert_eq!(
+        parse_mfm("<i>abc</i>"),
+        Node::Italic(Box::new(Node::Plain("abc".to_owned())))
+    );
+}
+
+#[test]
+fn italic2() {
+    assert_eq!(
+        parse_mfm("snake_case_name"),
+        Node::Plain("snake_case_name".to_owned())
+    );
+    assert_eq!(
+        parse_mfm("before*abc*after"),
+        Node::Plain("before*abc*after".to_owned())
+    );
+    assert_eq!(
+        parse_mfm("あいう*abc*えお"),
+        Node::Span(vec![
+            Node::Plain("あいう".to_owned()),
+            Node::Italic(Box::new(Node::Plain("abc".to_owned()))),
+            Node::Plain("えお".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn italic3() {
+    assert_eq!(parse_mfm("*a.b*"), Node::Plain("*a.b*".to_owned()));
+    assert_eq!(
+        parse_mfm("<i>a.b</i>"),
+        Node::Italic(Box::new(Node::Plain("a.b".to_owned())))
+    );
+}
+
+#[test]
+fn strike1() {
+    assert_eq!(
+        parse_mfm("~~abc~~"),
+        Node::Strike(Box::new(Node::Plain("abc".to_owned())))
+    );
+    assert_eq!(
+        parse_mfm("<s>abc</s>"),
+        Node::Strike(Box::new(Node::Plain("abc".to_owned())))
+    );
+}
+
+#[test]
+fn strike2() {
+    assert_eq!(parse_mfm("~~a~b~~"), Node::Plain("~~a~b~~".to_owned()));
+    assert_eq!(parse_mfm("~~a\nb~~"), Node::Plain("~~a\nb~~".to_owned()));
+    assert_eq!(
+        parse_mfm("<s>a\nb</s>"),
+        Node::Strike(Box::new(Node::Plain("a\nb".to_owned())))
+    );
+}
+
+#[test]
+fn nested_decoration() {
+    let node = parse_mfm("<b><i>abc</i> ~~def~~</b>");
+    assert_eq!(
+        node,
+        Node::Bold(Box::new(Node::Span(vec![
+            Node::Italic(Box::new(Node::Plain("abc".to_owned()))),
+            Node::Plain(" ".to_owned()),
+            Node::Strike(Box::new(Node::Plain("def".to_owned()))),
+        ])))
+    );
+}
+
+#[test]
+fn nest_limit() {
+    // Syntaxes deeper than 20 are left as text.
+    let s = format!("{}@alice{}", "<b>".repeat(21), "</b>".repeat(21));
+    let mut node = parse_mfm(&s);
+    for _ in 0..21 {
+        let Node::Bold(child) = node else {
+            panic!("{node:?}");
+        };
+        node = *child;
+    }
+    assert_eq!(node, Node::Plain("@alice".to_owned()));
+
+    let s = format!("{}@alice{}", "<b>".repeat(20), "</b>".repeat(20));
+    let mut node = parse_mfm(&s);
+    for _ in 0..20 {
+        let Node::Bold(child) = node else {
+            panic!("{node:?}");
+        };
+        node = *child;
+    }
+    assert_eq!(node, Node::LocalUser("alice".to_owned()));
+}
+
+#[test]
+fn inline_code1() {
+    let node = parse_mfm("run `cargo **build** @alice #tag` now");
+    assert_eq!(
+        node,
+        Node::Span(vec![
+            Node::Plain("run ".to_owned()),
+            Node::InlineCode("cargo **build** @alice #tag".to_owned()),
+            Node::Plain(" now".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn inline_code2() {
+    assert_eq!(parse_mfm("`a´b`"), Node::Plain("`a´b`".to_owned()));
+    assert_eq!(parse_mfm("`a\nb`"), Node::Plain("`a\nb`".to_owned()));
+    assert_eq!(parse_mfm("``"), Node::Plain("``".to_owned()));
+}
+
+#[test]
+fn code_block1() {
+    let node = parse_mfm("```\nabc\n```");
+    assert_eq!(
+        node,
+        Node::CodeBlock {
+            lang: None,
+            code: "abc".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn code_block2() {
+    let node = parse_mfm("```rust \nlet a = **b**;\n\n// @alice #tag\n```");
+    assert_eq!(
+        node,
+        Node::CodeBlock {
+            lang: Some("rust".to_owned()),
+            code: "let a = **b**;\n\n// @alice #tag".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn code_block3() {
+    let node = parse_mfm("aaa\n```\nabc\n```\nbbb");
+    assert_eq!(
+        node,
+        Node::Span(vec![
+            Node::Plain("aaa\n".to_owned()),
+            Node::CodeBlock {
+                lang: None,
+                code: "abc".to_owned(),
+            },
+            Node::Plain("\nbbb".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn code_block4() {
+    assert_eq!(
+        parse_mfm("aaa```\nabc\n```"),
+        Node::Plain("aaa```\nabc\n```".to_owned())
+    );
+    assert_eq!(
+        parse_mfm("```\nabc\n```bbb"),
+        Node::Plain("```\nabc\n```bbb".to_owned())
+    );
+}
+
+#[test]
+fn math_inline1() {
+    let node = parse_mfm(r"energy \(E = mc^2 **x**\) holds");
+    assert_eq!(
+        node,
+        Node::Span(vec![
+            Node::Plain("energy ".to_owned()),
+            Node::MathInline {
+                formula: "E = mc^2 **x**".to_owned(),
+                rendered: None,
+            },
+            Node::Plain(" holds".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn math_inline2() {
+    assert_eq!(
+        parse_mfm("\\(a\nb\\)"),
+        Node::Plain("\\(a\nb\\)".to_owned())
+    );
+    assert_eq!(parse_mfm(r"\(\)"), Node::Plain(r"\(\)".to_owned()));
+}
+
+#[test]
+fn math_block1() {
+    let node = parse_mfm("\\[\n\\sum_{i=1}^n i\n\\]");
+    assert_eq!(
+        node,
+        Node::MathBlock {
+            formula: r"\sum_{i=1}^n i".to_owned(),
+            rendered: None,
+        }
+    );
+
+    let node = parse_mfm(r"\[x^2\]");
+    assert_eq!(
+        node,
+        Node::MathBlock {
+            formula: "x^2".to_owned(),
+            rendered: None,
+        }
+    );
+}
+
+#[test]
+fn math_block2() {
+    assert_eq!(parse_mfm(r"a\[x\]"), Node::Plain(r"a\[x\]".to_owned()));
+    assert_eq!(parse_mfm(r"\[x\]b"), Node::Plain(r"\[x\]b".to_owned()));
+
+    let node = parse_mfm("a\n\\[x\\]\nb");
+    assert_eq!(
+        node,
+        Node::Span(vec![
+            Node::Plain("a\n".to_owned()),
+            Node::MathBlock {
+                formula: "x".to_owned(),
+                rendered: None,
+            },
+            Node::Plain("\nb".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn render_math() {
+    let node =
+        parse_mfm("**\\(x\\)**\n\\[y\\]").render_math(&|formula: &str, display| match display {
+            MathDisplay::Inline => Some(format!("<i>{formula}</i>")),
+            MathDisplay::Block => None,
+        });
+    assert_eq!(
+        node,
+        Node::Span(vec![
+            Node::Bold(Box::new(Node::MathInline {
+                formula: "x".to_owned(),
+                rendered: Some("<i>x</i>".to_owned()),
+            })),
+            Node::Plain("\n".to_owned()),
+            Node::MathBlock {
+                formula: "y".to_owned(),
+                rendered: None,
+            },
+        ])
+    );
+}
+
+#[test]
+fn url1() {
+    let node = parse_mfm("see https://example.com/a_(b)/?q=1#frag.");
+    assert_eq!(
+        node,
+        Node::Span(vec![
+            Node::Plain("see ".to_owned()),
+            Node::Url {
+                url: "https://example.com/a_(b)/?q=1#frag".to_owned(),
+                brackets: false,
+            },
+            Node::Plain(".".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn url2() {
+    let node = parse_mfm("https://example.com/(a");
+    assert_eq!(
+        node,
+        Node::Span(vec![
+            Node::Url {
+                url: "https://example.com/".to_owned(),
+                brackets: false,
+            },
+            Node::Plain("(a".to_owned()),
+        ])
+    );
+
+    assert_eq!(parse_mfm("http://"), Node::Plain("http://".to_owned()));
+    assert_eq!(
+        parse_mfm("https://.,"),
+        Node::Plain("https://.,".to_owned())
+    );
+}
+
+#[test]
+fn url3() {
+    let node = parse_mfm("<https://example.com/日本語>");
+    assert_eq!(
+        node,
+        Node::Url {
+            url: "https://example.com/日本語".to_owned(),
+            brackets: true,
+        }
+    );
+
+    assert_eq!(
+        parse_mfm("<https://example.com/a b>"),
+        Node::Span(vec![
+            Node::Plain("<".to_owned()),
+            Node::Url {
+                url: "https://example.com/a".to_owned(),
+                brackets: false,
+            },
+            Node::Plain(" b>".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn link1() {
+    let node = parse_mfm("[label **b**](https://example.com/a_(b))");
+    assert_eq!(
+        node,
+        Node::Link {
+            silent: false,
+            url: "https://example.com/a_(b)".to_owned(),
+            children: Box::new(Node::Span(vec![
+                Node::Plain("label ".to_owned()),
+                Node::Bold(Box::new(Node::Plain("b".to_owned()))),
+            ])),
+        }
+    );
+}
+
+#[test]
+fn link2() {
+    let node = parse_mfm("?[@alice #tag https://a.example](<https://example.com>)");
+    assert_eq!(
+        node,
+        Node::Link {
+            silent: true,
+            url: "https://example.com".to_owned(),
+            children: Box::new(Node::Plain("@alice #tag https://a.example".to_owned())),
+        }
+    );
+}
+
+#[test]
+fn link3() {
+    let node = parse_mfm("[a\nb](https://example.com)");
+    assert_eq!(
+        node,
+        Node::Span(vec![
+            Node::Plain("[a\nb](".to_owned()),
+            Node::Url {
+                url: "https://example.com".to_owned(),
+                brackets: false,
+            },
+            Node::Plain(")".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn host() {
+    assert_eq!(parse_host("example.com"), Some(vec!["example", "com"]));
+    assert_eq!(
+        parse_host("xn--r8jz45g.example."),
+        Some(vec!["xn--r8jz45g", "example", ""])
+    );
+    assert_eq!(parse_host("-bad.example"), None);
+    assert_eq!(parse_host("bad-.example"), None);
+    assert_eq!(parse_host("a_b.example"), None);
+    assert_eq!(parse_host("exa mple"), None);
+    assert_eq!(parse_host(&format!("{}.example", "a".repeat(64))), None);
+}
+
+#[test]
+fn mfm_fn1() {
+    let node = parse_mfm("$[fg.color=f00 **red**]");
+    assert_eq!(
+        node,
+        Node::Fn {
+            name: "fg".to_owned(),
+            args: [("color".to_owned(), Some("f00".to_owned()))].into(),
+            children: Box::new(Node::Bold(Box::new(Node::Plain("red".to_owned())))),
+        }
+    );
+    assert_eq!(
+        node.mfm_fn(),
+        Some(Ok(MfmFn::Fg {
+            color: "f00".to_owned()
+        }))
+    );
+}
+
+#[test]
+fn mfm_fn2() {
+    let node = parse_mfm("$[flip.h,v $[x2 :cat:]]");
+    assert_eq!(
+        node,
+        Node::Fn {
+            name: "flip".to_owned(),
+            args: [("h".to_owned(), None), ("v".to_owned(), None)].into(),
+            children: Box::new(Node::Fn {
+                name: "x2".to_owned(),
+                args: BTreeMap::new(),
+                children: Box::new(Node::LocalCustomEmoji("cat".to_owned())),
+            }),
+        }
+    );
+    assert_eq!(node.mfm_fn(), Some(Ok(MfmFn::Flip { h: true, v: true })));
+}
+
+#[test]
+fn mfm_fn3() {
+    assert_eq!(parse_mfm("$[x2]"), Node::Plain("$[x2]".to_owned()));
+    assert_eq!(parse_mfm("$[x2 ]"), Node::Plain("$[x2 ]".to_owned()));
+    assert_eq!(
+        parse_mfm("$[x2.a= b]"),
+        Node::Plain("$[x2.a= b]".to_owned())
+    );
+}
+
+#[test]
+fn mfm_fn_args() {
+    let args = |s: &[(&str, Option<&str>)]| -> BTreeMap<String, Option<String>> {
+        s.iter()
+            .map(|(k, v)| (k.to_string(), v.map(str::to_owned)))
+            .collect()
+    };
+
+    assert_eq!(
+        MfmFn::new("flip", &args(&[])),
+        Ok(MfmFn::Flip { h: true, v: false })
+    );
+    assert_eq!(
+        MfmFn::new("rotate", &args(&[])),
+        Ok(MfmFn::Rotate { deg: 90.0 })
+    );
+    assert_eq!(
+        MfmFn::new("position", &args(&[("x", Some("-1.5"))])),
+        Ok(MfmFn::Position { x: -1.5, y: 0.0 })
+    );
+    assert_eq!(
+        MfmFn::new("rainbow", &args(&[("speed", Some("0.5s"))])),
+        Ok(MfmFn::Rainbow {
+            speed: Some(0.5),
+            delay: None
+        })
+    );
+    assert_eq!(
+        MfmFn::new("rainbow", &args(&[("delay", Some("-1s"))])),
+        Ok(MfmFn::Rainbow {
+            speed: None,
+            delay: Some(-1.0)
+        })
+    );
+    assert_eq!(
+        MfmFn::new("rainbow", &args(&[("speed", Some("-s"))])),
+        Err(FnError::InvalidArg {
+            key: "speed".to_owned(),
+            value: Some("-s".to_owned())
+        })
+    );
+    assert_eq!(
+        MfmFn::new("rainbow", &args(&[("speed", Some("fast"))])),
+        Err(FnError::InvalidArg {
+            key: "speed".to_owned(),
+            value: Some("fast".to_owned())
+        })
+    );
+    assert_eq!(
+        MfmFn::new("scale", &args(&[("x", None)])),
+        Err(FnError::InvalidArg {
+            key: "x".to_owned(),
+            value: None
+        })
+    );
+    assert_eq!(
+        MfmFn::new("bg", &args(&[("color", Some("red"))])),
+        Err(FnError::InvalidArg {
+            key: "color".to_owned(),
+            value: Some("red".to_owned())
+        })
+    );
+    assert_eq!(
+        MfmFn::new("bg", &args(&[("color", Some("ff00ff00"))])),
+        Err(FnError::InvalidArg {
+            key: "color".to_owned(),
+            value: Some("ff00ff00".to_owned())
+        })
+    );
+    assert_eq!(
+        MfmFn::new("bg", &args(&[("color", Some("F0F0F"))])),
+        Ok(MfmFn::Bg {
+            color: "F0F0F".to_owned()
+        })
+    );
+    assert_eq!(
+        MfmFn::new("fg", &args(&[])),
+        Ok(MfmFn::Fg {
+            color: "f00".to_owned()
+        })
+    );
+    assert_eq!(
+        MfmFn::new("bg", &args(&[])),
+        Ok(MfmFn::Bg {
+            color: "0f0".to_owned()
+        })
+    );
+    assert_eq!(
+        MfmFn::new(
+            "border",
+            &args(&[("style", Some("dashed")), ("noclip", None)])
+        ),
+        Ok(MfmFn::Border {
+            style: BorderStyle::Dashed,
+            width: 1.0,
+            color: None,
+            radius: 0.0,
+            noclip: true
+        })
+    );
+    assert_eq!(
+        MfmFn::new("font", &args(&[("monospace", None)])),
+        Ok(MfmFn::Font(FontFamily::Monospace))
+    );
+    assert_eq!(
+        MfmFn::new("font", &args(&[("cursive", None), ("serif", None)])),
+        Ok(MfmFn::Font(FontFamily::Serif))
+    );
+    assert_eq!(
+        MfmFn::new("FONT", &args(&[("monospace", None)])),
+        Err(FnError::Unknown("FONT".to_owned()))
+    );
+    assert_eq!(
+        MfmFn::new("font", &args(&[])),
+        Err(FnError::MissingArg("family"))
+    );
+    assert_eq!(
+        MfmFn::new("tada", &args(&[])),
+        Err(FnError::Unknown("tada".to_owned()))
+    );
+}
+
+/// Unclosed openers used to make backtracking exponential, taking seconds for a dozen.
+#[test]
+fn unclosed_nesting() {
+    let start = std::time::Instant::now();
+    for open in ["$[x2 ", "<b>", "<small>", "<i>", "<s>"] {
+        let text = open.repeat(200);
+        assert_eq!(parse_mfm(&text), Node::Plain(text.clone()));
+
+        // A single closer at the end only lets the innermost ones match.
+        parse_mfm(&(open.repeat(12) + "]</b></small></i></s>"));
+    }
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+}
@@ -1,3 +1,3 @@
-pub const LINE_BREAK_PATTERNS: &'static [&'static str] = &[
+pub const LINE_BREAK_PATTERNS: &[&str] = &[
     "\r\n", "\r", "\n", "\u{000B}", "\u{000C}", "\u{0085}", "\u{2028}", "\u{2029}",
 ];
@@ -0,0 +1,141 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::mi_entities::note::ReactionAcceptance;
+
+/// A reaction as written in `notes/reactions/create` and in `Note::reactions` keys.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Reaction {
+    /// A unicode emoji such as `👍`. [`Reaction::decode`] also puts malformed custom emojis
+    /// here.
+    Unicode(String),
+
+    /// A custom emoji, `:name:` if `host` is `None` and `:name@host:` otherwise.
+    Custom { name: String, host: Option<String> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseReactionError;
+
+impl Reaction {
+    /// The reaction every note accepts, including [`ReactionAcceptance::LikeOnly`] ones.
+    pub fn like() -> Self {
+        Reaction::Unicode("❤".to_owned())
+    }
+
+    pub fn local(name: impl Into<String>) -> Self {
+        Reaction::Custom {
+            name: name.into(),
+            host: None,
+        }
+    }
+
+    /// Decodes a reaction sent by the server, such as a `Note::reactions` key.
+    ///
+    /// Unlike [`FromStr`], anything not in the custom emoji form is kept as
+    /// [`Reaction::Unicode`], same as Misskey's `decodeReaction`.
+    pub fn decode(s: &str) -> Self {
+        s.parse()
+            .unwrap_or_else(|_| Reaction::Unicode(s.to_owned()))
+    }
+
+    pub fn is_like(&self) -> bool {
+        matches!(self, Reaction::Unicode(s) if s == "❤" || s == "❤\u{FE0F}")
+    }
+
+    /// Whether a local user may send this reaction to a note with `acceptance`.
+    ///
+    /// Sensitivity of custom emojis isn't known client-side, so only the like-only
+    /// restriction is checked.
+    pub fn is_accepted_by(&self, acceptance: Option<ReactionAcceptance>) -> bool {
+        match acceptance {
+            Some(ReactionAcceptance::LikeOnly) => self.is_like(),
+            Some(ReactionAcceptance::LikeOnlyForRemote)
+            | Some(ReactionAcceptance::NonSensitiveOnly)
+            | Some(ReactionAcceptance::NonSensitiveOnlyForLocalLikeOnlyForRemote)
+            | None => true,
+        }
+    }
+}
+
+impl FromStr for Reaction {
+    type Err = ParseReactionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseReactionError);
+        }
+
+        let custom = s
+            .strip_prefix(':')
+            .and_then(|x| x.strip_suffix(':'))
+            .filter(|x| !x.is_empty());
+        let Some(custom) = custom else {
+            return Ok(Reaction::Unicode(s.to_owned()));
+        };
+
+        let (name, host) = match custom.split_once('@') {
+            // Local emojis appear as `:name@.:` in `Note::reactions`.
+            Some((name, ".")) => (name, None),
+            Some((name, host)) => (name, Some(host)),
+            None => (custom, None),
+        };
+
+        let is_name = |x: &str| {
+            !x.is_empty()
+                && x.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-'))
+        };
+        let is_host = |x: &str| {
+            !x.is_empty()
+                && x.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        };
+        if !is_name(name) || !host.is_none_or(is_host) {
+            return Err(ParseReactionError);
+        }
+
+        Ok(Reaction::Custom {
+            name: name.to_owned(),
+            host: host.map(str::to_owned),
+        })
+    }
+}
+
+impl fmt::Display for Reaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reaction::Unicode(s) => f.write_str(s),
+            Reaction::Custom { name, host: None } => write!(f, ":{name}:"),
+            Reaction::Custom {
+                name,
+                host: Some(host),
+            } => write!(f, ":{name}@{host}:"),
+        }
+    }
+}
+
+impl fmt::Display for ParseReactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid reaction")
+    }
+}
+
+impl std::error::Error for ParseReactionError {}
+
+impl Serialize for Reaction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Reaction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Ok(Reaction::decode(&s))
+    }
+}
+
+#[cfg(test)]
+mod tests;
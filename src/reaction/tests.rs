@@ -0,0 +1,72 @@
+use super::*;
+
+#[test]
+fn unicode() {
+    let reaction: Reaction = "👍".parse().unwrap();
+    assert_eq!(reaction, Reaction::Unicode("👍".to_owned()));
+    assert_eq!(reaction.to_string(), "👍");
+}
+
+#[test]
+fn local_custom() {
+    let reaction: Reaction = ":blobcat:".parse().unwrap();
+    assert_eq!(reaction, Reaction::local("blobcat"));
+    assert_eq!(reaction.to_string(), ":blobcat:");
+}
+
+#[test]
+fn local_custom_with_dot_host() {
+    let reaction: Reaction = ":blobcat@.:".parse().unwrap();
+    assert_eq!(reaction, Reaction::local("blobcat"));
+    assert_eq!(reaction.to_string(), ":blobcat:");
+}
+
+#[test]
+fn remote_custom() {
+    let reaction: Reaction = ":blobcat@misskey.example:".parse().unwrap();
+    assert_eq!(
+        reaction,
+        Reaction::Custom {
+            name: "blobcat".to_owned(),
+            host: Some("misskey.example".to_owned()),
+        }
+    );
+    assert_eq!(reaction.to_string(), ":blobcat@misskey.example:");
+}
+
+#[test]
+fn invalid() {
+    assert_eq!("".parse::<Reaction>(), Err(ParseReactionError));
+    assert_eq!(":bad name:".parse::<Reaction>(), Err(ParseReactionError));
+    assert_eq!(
+        "::".parse::<Reaction>(),
+        Ok(Reaction::Unicode("::".to_owned()))
+    );
+}
+
+#[test]
+fn decode_fallback() {
+    assert_eq!(
+        Reaction::decode(":bad name:"),
+        Reaction::Unicode(":bad name:".to_owned())
+    );
+    assert_eq!(
+        Reaction::decode(":blobcat@bad host:"),
+        Reaction::Unicode(":blobcat@bad host:".to_owned())
+    );
+    assert_eq!(Reaction::decode(":blobcat@.:"), Reaction::local("blobcat"));
+
+    let reaction: Reaction = serde_json::from_str(r#"":bad name:""#).unwrap();
+    assert_eq!(reaction, Reaction::Unicode(":bad name:".to_owned()));
+}
+
+#[test]
+fn acceptance() {
+    let custom = Reaction::local("blobcat");
+
+    assert!(Reaction::like().is_accepted_by(Some(ReactionAcceptance::LikeOnly)));
+    assert!(Reaction::Unicode("❤️".to_owned()).is_accepted_by(Some(ReactionAcceptance::LikeOnly)));
+    assert!(!custom.is_accepted_by(Some(ReactionAcceptance::LikeOnly)));
+    assert!(custom.is_accepted_by(Some(ReactionAcceptance::NonSensitiveOnly)));
+    assert!(custom.is_accepted_by(None));
+}
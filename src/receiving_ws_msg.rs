@@ -1,10 +1,19 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
 
-use crate::mi_entities::Note;
+use crate::{
+    common::Int,
+    mi_entities::{
+        note::NotePoll, Announcement, Antenna, DriveFile, MeDetailed, Note, Notification,
+        UserDetailedNotMe, UserLite,
+    },
+    reaction::Reaction,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "body")]
 #[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
 pub enum ReceivingWsMsg {
     #[serde(rename = "channel")]
     Channel(WsMsgChannelBody),
@@ -24,6 +33,313 @@ pub enum WsMsgChannelBody {
 
     #[serde(rename = "reply")]
     Reply { id: String, body: Note },
+
+    /// `main`: a note of mine was renoted.
+    #[serde(rename = "renote")]
+    Renote { id: String, body: Note },
+
+    /// `main`
+    #[serde(rename = "notification")]
+    Notification { id: String, body: Box<Notification> },
+
+    /// `main`
+    #[serde(rename = "unreadNotification")]
+    UnreadNotification { id: String, body: Box<Notification> },
+
+    /// `main`
+    #[serde(rename = "readAllNotifications")]
+    ReadAllNotifications { id: String },
+
+    /// `main`
+    #[serde(rename = "notificationFlushed")]
+    NotificationFlushed { id: String },
+
+    /// `main`: id of the note.
+    #[serde(rename = "unreadMention")]
+    UnreadMention { id: String, body: String },
+
+    /// `main`
+    #[serde(rename = "readAllUnreadMentions")]
+    ReadAllUnreadMentions { id: String },
+
+    /// `main`: id of the note.
+    #[serde(rename = "unreadSpecifiedNote")]
+    UnreadSpecifiedNote { id: String, body: String },
+
+    /// `main`
+    #[serde(rename = "readAllUnreadSpecifiedNotes")]
+    ReadAllUnreadSpecifiedNotes { id: String },
+
+    /// `main`: I followed someone.
+    #[serde(rename = "follow")]
+    Follow {
+        id: String,
+        body: Box<UserDetailedNotMe>,
+    },
+
+    /// `main`: someone followed me.
+    #[serde(rename = "followed")]
+    Followed { id: String, body: Box<UserLite> },
+
+    /// `main`: I unfollowed someone.
+    #[serde(rename = "unfollow")]
+    Unfollow {
+        id: String,
+        body: Box<UserDetailedNotMe>,
+    },
+
+    /// `main`
+    #[serde(rename = "receiveFollowRequest")]
+    ReceiveFollowRequest { id: String, body: Box<UserLite> },
+
+    /// `main`
+    #[serde(rename = "meUpdated")]
+    MeUpdated { id: String, body: Box<MeDetailed> },
+
+    /// `main`
+    #[serde(rename = "myTokenRegenerated")]
+    MyTokenRegenerated { id: String },
+
+    /// `main`
+    #[serde(rename = "signin")]
+    Signin { id: String, body: SigninBody },
+
+    /// `main`
+    #[serde(rename = "registryUpdated")]
+    RegistryUpdated {
+        id: String,
+        body: RegistryUpdatedBody,
+    },
+
+    /// `main`
+    #[serde(rename = "driveFileCreated")]
+    DriveFileCreated { id: String, body: Box<DriveFile> },
+
+    /// `main`: a file requested by `drive/files/upload-from-url` is ready.
+    #[serde(rename = "urlUploadFinished")]
+    UrlUploadFinished {
+        id: String,
+        body: UrlUploadFinishedBody,
+    },
+
+    /// `main`
+    #[serde(rename = "announcementCreated")]
+    AnnouncementCreated {
+        id: String,
+        body: AnnouncementCreatedBody,
+    },
+
+    /// `main`
+    #[serde(rename = "readAllAnnouncements")]
+    ReadAllAnnouncements { id: String },
+
+    /// `main`
+    #[serde(rename = "unreadAntenna")]
+    UnreadAntenna { id: String, body: Box<Antenna> },
+
+    /// `main`
+    #[serde(rename = "readAntenna")]
+    ReadAntenna { id: String, body: Box<Antenna> },
+
+    /// `main`
+    #[serde(rename = "pageEvent")]
+    PageEvent { id: String, body: PageEventBody },
+
+    /// `serverStats` and `queueStats`: sent every couple of seconds.
+    #[serde(rename = "stats")]
+    Stats { id: String, body: StatsBody },
+
+    /// `serverStats` and `queueStats`: reply to
+    /// [`SendingWsMsg::request_log`](crate::sending_ws_msg::SendingWsMsg::request_log), newest first.
+    #[serde(rename = "statsLog")]
+    StatsLog { id: String, body: Vec<StatsBody> },
+
+    /// `main`: the body is a chat message, which isn't modelled yet.
+    #[serde(rename = "newChatMessage")]
+    NewChatMessage { id: String, body: serde_json::Value },
+
+    /// A type not modelled above. Modelled types with a malformed body are errors.
+    #[serde(untagged)]
+    Unknown {
+        id: String,
+        #[serde(deserialize_with = "unmodelled_type")]
+        r#type: String,
+        #[serde(default)]
+        body: serde_json::Value,
+    },
+}
+
+/// Types of [`WsMsgChannelBody`] other than `Unknown`. Keep in sync with the variants.
+const CHANNEL_TYPES: &[&str] = &[
+    "note",
+    "mention",
+    "reply",
+    "renote",
+    "notification",
+    "unreadNotification",
+    "readAllNotifications",
+    "notificationFlushed",
+    "unreadMention",
+    "readAllUnreadMentions",
+    "unreadSpecifiedNote",
+    "readAllUnreadSpecifiedNotes",
+    "follow",
+    "followed",
+    "unfollow",
+    "receiveFollowRequest",
+    "meUpdated",
+    "myTokenRegenerated",
+    "signin",
+    "registryUpdated",
+    "driveFileCreated",
+    "urlUploadFinished",
+    "announcementCreated",
+    "readAllAnnouncements",
+    "unreadAntenna",
+    "readAntenna",
+    "pageEvent",
+    "stats",
+    "statsLog",
+    "newChatMessage",
+];
+
+fn unmodelled_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let r#type = String::deserialize(deserializer)?;
+    if CHANNEL_TYPES.contains(&r#type.as_str()) {
+        return Err(D::Error::custom(format!("malformed {type} message")));
+    }
+    Ok(r#type)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StatsBody {
+    Server(ServerStats),
+    Queue(QueueStats),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServerStats {
+    /// Usage of all cores, from 0 to 1.
+    pub cpu: f64,
+    pub mem: MemStats,
+    pub net: NetStats,
+    pub fs: FsStats,
+}
+
+/// In bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemStats {
+    pub used: f64,
+    pub active: f64,
+}
+
+/// In bytes per second.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetStats {
+    pub rx: f64,
+    pub tx: f64,
+}
+
+/// In bytes per second.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FsStats {
+    pub r: f64,
+    pub w: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueStats {
+    pub deliver: JobQueueStats,
+    pub inbox: JobQueueStats,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobQueueStats {
+    /// Jobs processed since the previous stats.
+    pub active_since_prev_tick: Int,
+    pub active: Int,
+    pub waiting: Int,
+    pub delayed: Int,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigninBody {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub ip: String,
+    pub headers: serde_json::Value,
+    pub success: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryUpdatedBody {
+    pub scope: Vec<String>,
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UrlUploadFinishedBody {
+    /// As given to `drive/files/upload-from-url`.
+    pub marker: Option<String>,
+    pub file: Box<DriveFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnouncementCreatedBody {
+    pub announcement: Box<Announcement>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageEventBody {
+    pub page_id: String,
+    pub event: String,
+    pub var: serde_json::Value,
+    pub user_id: String,
+    pub user: Box<UserLite>,
+}
+
+impl WsMsgChannelBody {
+    /// The [`ChannelConnection`](crate::ws_connection::ChannelConnection) id this was sent to.
+    pub fn id(&self) -> &str {
+        match self {
+            WsMsgChannelBody::Note { id, .. }
+            | WsMsgChannelBody::Mention { id, .. }
+            | WsMsgChannelBody::Reply { id, .. }
+            | WsMsgChannelBody::Renote { id, .. }
+            | WsMsgChannelBody::Notification { id, .. }
+            | WsMsgChannelBody::UnreadNotification { id, .. }
+            | WsMsgChannelBody::ReadAllNotifications { id }
+            | WsMsgChannelBody::NotificationFlushed { id }
+            | WsMsgChannelBody::UnreadMention { id, .. }
+            | WsMsgChannelBody::ReadAllUnreadMentions { id }
+            | WsMsgChannelBody::UnreadSpecifiedNote { id, .. }
+            | WsMsgChannelBody::ReadAllUnreadSpecifiedNotes { id }
+            | WsMsgChannelBody::Follow { id, .. }
+            | WsMsgChannelBody::Followed { id, .. }
+            | WsMsgChannelBody::Unfollow { id, .. }
+            | WsMsgChannelBody::ReceiveFollowRequest { id, .. }
+            | WsMsgChannelBody::MeUpdated { id, .. }
+            | WsMsgChannelBody::MyTokenRegenerated { id }
+            | WsMsgChannelBody::Signin { id, .. }
+            | WsMsgChannelBody::RegistryUpdated { id, .. }
+            | WsMsgChannelBody::DriveFileCreated { id, .. }
+            | WsMsgChannelBody::UrlUploadFinished { id, .. }
+            | WsMsgChannelBody::AnnouncementCreated { id, .. }
+            | WsMsgChannelBody::ReadAllAnnouncements { id }
+            | WsMsgChannelBody::UnreadAntenna { id, .. }
+            | WsMsgChannelBody::ReadAntenna { id, .. }
+            | WsMsgChannelBody::PageEvent { id, .. }
+            | WsMsgChannelBody::Stats { id, .. }
+            | WsMsgChannelBody::StatsLog { id, .. }
+            | WsMsgChannelBody::NewChatMessage { id, .. }
+            | WsMsgChannelBody::Unknown { id, .. } => id,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,20 +350,99 @@ pub enum NoteUpdatedBody {
         id: String,
         body: NoteUpdatedBodyReactedBody,
     },
+
+    #[serde(rename = "unreacted")]
+    NoteUpdatedBodyUnreacted {
+        id: String,
+        body: NoteUpdatedBodyUnreactedBody,
+    },
+
+    #[serde(rename = "deleted")]
+    NoteUpdatedBodyDeleted {
+        id: String,
+        body: NoteUpdatedBodyDeletedBody,
+    },
+
+    #[serde(rename = "pollVoted")]
+    NoteUpdatedBodyPollVoted {
+        id: String,
+        body: NoteUpdatedBodyPollVotedBody,
+    },
+
+    /// The note was edited.
+    #[serde(rename = "updated")]
+    NoteUpdatedBodyUpdated {
+        id: String,
+        body: NoteUpdatedBodyUpdatedBody,
+    },
+}
+
+impl NoteUpdatedBody {
+    /// Id of the updated note.
+    pub fn id(&self) -> &str {
+        match self {
+            NoteUpdatedBody::NoteUpdatedBodyReacted { id, .. }
+            | NoteUpdatedBody::NoteUpdatedBodyUnreacted { id, .. }
+            | NoteUpdatedBody::NoteUpdatedBodyDeleted { id, .. }
+            | NoteUpdatedBody::NoteUpdatedBodyPollVoted { id, .. }
+            | NoteUpdatedBody::NoteUpdatedBodyUpdated { id, .. } => id,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NoteUpdatedBodyReactedBody {
+    /// Set for custom emojis, named `name@host` or `name@.` if local.
     pub emoji: Option<Emoji>,
 
-    pub reaction: String,
+    pub reaction: Reaction,
+
+    #[serde(rename = "userId")]
+    pub user_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteUpdatedBodyUnreactedBody {
+    pub reaction: Reaction,
+
+    #[serde(rename = "userId")]
+    pub user_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteUpdatedBodyDeletedBody {
+    #[serde(rename = "deletedAt")]
+    pub deleted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteUpdatedBodyUpdatedBody {
+    #[serde(default)]
+    pub cw: Option<String>,
+
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteUpdatedBodyPollVotedBody {
+    pub choice: usize,
 
     #[serde(rename = "userId")]
     pub user_id: String,
 }
 
+impl NoteUpdatedBodyPollVotedBody {
+    /// Counts this vote in `poll`, marking it as voted if `my_user_id` cast it.
+    pub fn apply_to(&self, poll: &mut NotePoll, my_user_id: Option<&str>) -> bool {
+        poll.apply_vote(self.choice, my_user_id == Some(self.user_id.as_str()))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Emoji {
-    name: String,
-    url: String,
+    pub name: String,
+    pub url: String,
 }
+
+#[cfg(test)]
+mod tests;
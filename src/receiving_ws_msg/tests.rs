@@ -0,0 +1,149 @@
+use serde_json::json;
+
+use super::*;
+use crate::{
+    fixtures,
+    mi_entities::{note::PollChoice, notification::NotificationType},
+};
+
+#[test]
+fn poll_voted() {
+    let msg: ReceivingWsMsg = serde_json::from_str(
+        r#"{"type":"noteUpdated","body":{"id":"9xyz000001","type":"pollVoted","body":{"choice":1,"userId":"9abc000001"}}}"#,
+    )
+    .unwrap();
+    let ReceivingWsMsg::NoteUpdated(NoteUpdatedBody::NoteUpdatedBodyPollVoted { id, body }) = msg
+    else {
+        panic!("{msg:?}");
+    };
+    assert_eq!(id, "9xyz000001");
+
+    let mut poll = NotePoll {
+        expires_at: None,
+        multiple: false,
+        choices: vec![
+            PollChoice {
+                is_voted: false,
+                text: "a".to_owned(),
+                votes: 2,
+            },
+            PollChoice {
+                is_voted: false,
+                text: "b".to_owned(),
+                votes: 0,
+            },
+        ],
+    };
+    assert!(body.apply_to(&mut poll, Some("9abc000001")));
+    assert_eq!(poll.choices[1].votes, 1);
+    assert!(poll.choices[1].is_voted);
+    assert_eq!(poll.total_votes(), 3);
+}
+
+fn channel_body(json: &str) -> WsMsgChannelBody {
+    let msg: ReceivingWsMsg = serde_json::from_str(json).unwrap();
+    let ReceivingWsMsg::Channel(body) = msg else {
+        panic!("{msg:?}");
+    };
+    body
+}
+
+#[test]
+fn main_followed() {
+    let body = channel_body(
+        &json!({
+            "type": "channel",
+            "body": {"id": "main", "type": "followed", "body": fixtures::user()}
+        })
+        .to_string(),
+    );
+    let WsMsgChannelBody::Followed { id, body } = body else {
+        panic!("{body:?}");
+    };
+    assert_eq!(id, "main");
+    assert_eq!(body.username, "alice");
+}
+
+#[test]
+fn main_notification() {
+    let notification = json!({
+        "id": "9n00000001",
+        "createdAt": "2025-01-01T00:00:00.000Z",
+        "type": "reaction",
+        "userId": "9abc000001",
+        "user": fixtures::user(),
+        "reaction": ":blobcat@.:"
+    });
+    let body = channel_body(
+        &json!({
+            "type": "channel",
+            "body": {"id": "main", "type": "notification", "body": notification}
+        })
+        .to_string(),
+    );
+    let WsMsgChannelBody::Notification { body, .. } = body else {
+        panic!("{body:?}");
+    };
+    assert_eq!(body.r#type, NotificationType::Reaction);
+    assert_eq!(body.reaction, Some(Reaction::local("blobcat")));
+    assert_eq!(body.user.unwrap().id, "9abc000001");
+
+    let body = channel_body(
+        r#"{"type":"channel","body":{"id":"main","type":"notification","body":{"id":"9n00000002","createdAt":"2025-01-01T00:00:00.000Z","type":"somethingNew"}}}"#,
+    );
+    let WsMsgChannelBody::Notification { body, .. } = body else {
+        panic!("{body:?}");
+    };
+    assert_eq!(body.r#type, NotificationType::Unknown);
+}
+
+#[test]
+fn main_without_body() {
+    let body =
+        channel_body(r#"{"type":"channel","body":{"id":"main","type":"readAllNotifications"}}"#);
+    assert!(matches!(
+        body,
+        WsMsgChannelBody::ReadAllNotifications { .. }
+    ));
+    assert_eq!(body.id(), "main");
+
+    let body = channel_body(
+        r#"{"type":"channel","body":{"id":"main","type":"unreadMention","body":"9xyz000001"}}"#,
+    );
+    let WsMsgChannelBody::UnreadMention { body, .. } = body else {
+        panic!("{body:?}");
+    };
+    assert_eq!(body, "9xyz000001");
+}
+
+#[test]
+fn unknown_channel_type() {
+    let body = channel_body(
+        r#"{"type":"channel","body":{"id":"main","type":"somethingNew","body":{"a":1}}}"#,
+    );
+    let WsMsgChannelBody::Unknown { id, r#type, body } = &body else {
+        panic!("{body:?}");
+    };
+    assert_eq!(id, "main");
+    assert_eq!(r#type, "somethingNew");
+    assert_eq!(body, &json!({"a": 1}));
+
+    let body = channel_body(r#"{"type":"channel","body":{"id":"main","type":"somethingNew"}}"#);
+    assert!(matches!(
+        body,
+        WsMsgChannelBody::Unknown {
+            body: serde_json::Value::Null,
+            ..
+        }
+    ));
+    assert_eq!(body.id(), "main");
+}
+
+#[test]
+fn malformed_known_type() {
+    let res = serde_json::from_value::<ReceivingWsMsg>(json!({
+        "type": "channel",
+        "body": {"id": "c", "type": "note", "body": {"id": "x"}}
+    }));
+    assert!(res.is_err());
+}
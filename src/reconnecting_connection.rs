@@ -0,0 +1,233 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    receiving_ws_msg::ReceivingWsMsg,
+    sending_ws_msg::SendingWsMsg,
+    ws_connection::{stream_url, ChannelConnection, MiChannel, WsConnection},
+    Client,
+};
+
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum ReconnectingEvent {
+    Message(ReceivingWsMsg),
+
+    /// The socket was reestablished and channels and note subscriptions were restored.
+    /// Anything sent in between was missed, so backfill it through REST if needed.
+    Reconnected,
+}
+
+/// Exponential backoff between reconnection attempts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+
+    /// Gives up after this many consecutive failures, counting connections that dropped
+    /// before delivering a message. `None` retries forever.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+            max_retries: None,
+        }
+    }
+}
+
+impl Backoff {
+    fn delay(&self, attempt: u32) -> Duration {
+        self.initial
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max)
+    }
+}
+
+/// A connection that stayed up this long is healthy, so the backoff starts over.
+const STABLE_AFTER: Duration = Duration::from_secs(30);
+
+/// A [`WsConnection`] that reconnects when the socket drops.
+///
+/// Channels keep their [`ChannelConnection`] ids across reconnections, so messages can
+/// still be matched against them.
+pub struct ReconnectingConnection {
+    url: String,
+    ws: WsConnection,
+    backoff: Backoff,
+    channels: BTreeMap<String, MiChannel>,
+    sub_notes: BTreeSet<String>,
+    reconnected: bool,
+
+    /// Failed attempts and connections dropped before proving healthy, in a row.
+    attempt: u32,
+    connected_at: Instant,
+}
+
+impl ReconnectingConnection {
+    pub async fn new(
+        host: impl AsRef<str>,
+        api_key: Option<impl AsRef<str>>,
+    ) -> anyhow::Result<ReconnectingConnection> {
+        Self::connect(stream_url(host, api_key)).await
+    }
+
+    pub async fn from_client(client: &Client) -> anyhow::Result<ReconnectingConnection> {
+        Self::connect(client.streaming_url()?.to_string()).await
+    }
+
+    async fn connect(url: String) -> anyhow::Result<ReconnectingConnection> {
+        let ws = WsConnection::connect(&url).await?;
+
+        Ok(ReconnectingConnection {
+            url,
+            ws,
+            backoff: Backoff::default(),
+            channels: BTreeMap::new(),
+            sub_notes: BTreeSet::new(),
+            reconnected: false,
+            attempt: 0,
+            connected_at: Instant::now(),
+        })
+    }
+
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    pub async fn connect_to_channel(
+        &mut self,
+        channel: MiChannel,
+    ) -> anyhow::Result<ChannelConnection> {
+        // Bad params would fail every replay, so reject them before keeping the channel.
+        channel.params()?;
+        let id = Uuid::new_v4().to_string();
+        self.channels.insert(id.clone(), channel.clone());
+
+        match self
+            .ws
+            .connect_to_channel_with_id(&channel, id.clone())
+            .await
+        {
+            Ok(conn) => Ok(conn),
+            Err(err) => {
+                warn!("connecting to channel failed, reconnecting: {err:?}");
+                if let Err(err) = self.reconnect().await {
+                    self.channels.remove(&id);
+                    return Err(err);
+                }
+                Ok(ChannelConnection { id })
+            }
+        }
+    }
+
+    /// Sends `msg`, reconnecting first if the socket has dropped.
+    pub async fn send(&mut self, msg: SendingWsMsg) -> anyhow::Result<()> {
+        match &msg {
+            SendingWsMsg::SubNote(body) => {
+                self.sub_notes.insert(body.id.clone());
+            }
+            SendingWsMsg::UnsubNote(body) => {
+                self.sub_notes.remove(&body.id);
+            }
+            SendingWsMsg::Disconnect(body) => {
+                self.channels.remove(&body.id);
+            }
+            SendingWsMsg::Ch(_) => {}
+        }
+
+        match self.ws.send(msg.clone()).await {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(_)) => info!("disconnected"),
+            Err(err) => warn!("connection error: {err:?}"),
+        }
+
+        self.reconnect().await?;
+        // Subscriptions were already replayed, and dropped ones simply weren't.
+        if matches!(msg, SendingWsMsg::Ch(_)) && self.ws.send(msg).await?.is_err() {
+            bail!("disconnected again before the message was resent");
+        }
+        Ok(())
+    }
+
+    /// Receives the next message, reconnecting as long as the backoff allows.
+    pub async fn receive(&mut self) -> anyhow::Result<ReconnectingEvent> {
+        if std::mem::take(&mut self.reconnected) {
+            return Ok(ReconnectingEvent::Reconnected);
+        }
+
+        match self.ws.receive().await {
+            Ok(Ok(msg)) => {
+                self.attempt = 0;
+                return Ok(ReconnectingEvent::Message(msg));
+            }
+            Ok(Err(_)) => info!("disconnected"),
+            Err(err) => warn!("connection error: {err:?}"),
+        }
+
+        self.reconnect().await?;
+        self.reconnected = false;
+        Ok(ReconnectingEvent::Reconnected)
+    }
+
+    /// Waits for the backoff unless the dropped connection was healthy, so that a server
+    /// closing every socket right away isn't hammered.
+    async fn reconnect(&mut self) -> anyhow::Result<()> {
+        if self.connected_at.elapsed() >= STABLE_AFTER {
+            self.attempt = 0;
+        }
+        loop {
+            if self.attempt > 0 {
+                let delay = self.backoff.delay(self.attempt - 1);
+                info!("reconnecting in {delay:?}");
+                tokio::time::sleep(delay).await;
+            }
+            match self.try_reconnect().await {
+                Ok(()) => {
+                    info!("reconnected");
+                    self.attempt += 1;
+                    self.connected_at = Instant::now();
+                    self.reconnected = true;
+                    return Ok(());
+                }
+                Err(err) => {
+                    if self.backoff.max_retries.is_some_and(|n| self.attempt >= n) {
+                        bail!(
+                            "giving up reconnecting after {} retries: {err:?}",
+                            self.attempt
+                        );
+                    }
+                    warn!("reconnecting failed: {err:?}");
+                    self.attempt += 1;
+                }
+            }
+        }
+    }
+
+    async fn try_reconnect(&mut self) -> anyhow::Result<()> {
+        let mut ws = WsConnection::connect(&self.url).await?;
+        for (id, channel) in &self.channels {
+            ws.connect_to_channel_with_id(channel, id.clone()).await?;
+        }
+        for id in &self.sub_notes {
+            if ws.send(SendingWsMsg::sub_note(id.clone())).await?.is_err() {
+                bail!("disconnected while restoring subscriptions");
+            }
+        }
+        self.ws = ws;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
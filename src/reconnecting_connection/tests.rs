@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use serde_json::Value;
+use tokio::net::TcpListener;
+use tokio_tungstenite::accept_async;
+
+use super::*;
+
+fn text_json(msg: tokio_tungstenite::tungstenite::Message) -> Value {
+    serde_json::from_str(msg.to_text().unwrap()).unwrap()
+}
+
+#[test]
+fn backoff_doubles_up_to_max() {
+    let backoff = Backoff::default();
+
+    assert_eq!(backoff.delay(0), Duration::from_secs(1));
+    assert_eq!(backoff.delay(1), Duration::from_secs(2));
+    assert_eq!(backoff.delay(5), Duration::from_secs(32));
+    assert_eq!(backoff.delay(6), Duration::from_secs(60));
+    assert_eq!(backoff.delay(100), Duration::from_secs(60));
+}
+
+#[tokio::test]
+async fn replays_after_reconnecting() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/stream", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let mut sent = Vec::new();
+        for _ in 0..2 {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+
+            let connect = text_json(ws.next().await.unwrap().unwrap());
+            let sub_note = text_json(ws.next().await.unwrap().unwrap());
+            sent.push((connect, sub_note));
+            ws.close(None).await.unwrap();
+        }
+        sent
+    });
+
+    let mut conn = ReconnectingConnection::connect(url)
+        .await
+        .unwrap()
+        .with_backoff(Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(10),
+            max_retries: Some(3),
+        });
+    let home = conn
+        .connect_to_channel(MiChannel::Home(Default::default()))
+        .await
+        .unwrap();
+    conn.send(SendingWsMsg::sub_note("9note00001".to_owned()))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        conn.receive().await.unwrap(),
+        ReconnectingEvent::Reconnected
+    ));
+
+    let sent = server.await.unwrap();
+    assert_eq!(sent.len(), 2);
+    for (connect, sub_note) in &sent {
+        assert_eq!(connect["type"], "connect");
+        assert_eq!(connect["body"]["id"], home.id.as_str());
+        assert_eq!(connect["body"]["channel"], "homeTimeline");
+        assert_eq!(sub_note["type"], "subNote");
+        assert_eq!(sub_note["body"]["id"], "9note00001");
+    }
+}
+
+#[tokio::test]
+async fn backs_off_when_dropped_right_away() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/stream", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            ws.close(None).await.unwrap();
+        }
+    });
+
+    let delay = Duration::from_millis(50);
+    let mut conn = ReconnectingConnection::connect(url)
+        .await
+        .unwrap()
+        .with_backoff(Backoff {
+            initial: delay,
+            max: delay,
+            max_retries: None,
+        });
+
+    // The first reconnection is immediate, the later ones wait as none of them delivered.
+    let start = tokio::time::Instant::now();
+    for _ in 0..3 {
+        assert!(matches!(
+            conn.receive().await.unwrap(),
+            ReconnectingEvent::Reconnected
+        ));
+    }
+    assert!(start.elapsed() >= delay * 2);
+}
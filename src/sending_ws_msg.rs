@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "body")]
@@ -6,6 +7,16 @@ use serde::{Deserialize, Serialize};
 pub enum SendingWsMsg {
     #[serde(rename = "subNote")]
     SubNote(WsMsgSubNoteBody),
+
+    #[serde(rename = "unsubNote")]
+    UnsubNote(WsMsgSubNoteBody),
+
+    #[serde(rename = "disconnect")]
+    Disconnect(WsMsgDisconnectBody),
+
+    /// A message to a connected channel, e.g. `reversiGame` moves.
+    #[serde(rename = "ch")]
+    Ch(WsMsgChBody),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,8 +24,63 @@ pub struct WsMsgSubNoteBody {
     pub id: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WsMsgDisconnectBody {
+    /// Id of the [`ChannelConnection`](crate::ws_connection::ChannelConnection).
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WsMsgChBody {
+    /// Id of the [`ChannelConnection`](crate::ws_connection::ChannelConnection).
+    pub id: String,
+
+    #[serde(rename = "type")]
+    pub r#type: String,
+
+    #[serde(default)]
+    pub body: serde_json::Value,
+}
+
 impl SendingWsMsg {
     pub fn sub_note(id: String) -> SendingWsMsg {
         SendingWsMsg::SubNote(WsMsgSubNoteBody { id })
     }
+
+    pub fn unsub_note(id: String) -> SendingWsMsg {
+        SendingWsMsg::UnsubNote(WsMsgSubNoteBody { id })
+    }
+
+    pub fn disconnect(id: String) -> SendingWsMsg {
+        SendingWsMsg::Disconnect(WsMsgDisconnectBody { id })
+    }
+
+    pub fn ch(id: String, r#type: impl Into<String>, body: serde_json::Value) -> SendingWsMsg {
+        SendingWsMsg::Ch(WsMsgChBody {
+            id,
+            r#type: r#type.into(),
+            body,
+        })
+    }
+
+    /// Asks a `serverStats` or `queueStats` channel for up to `length` past stats.
+    ///
+    /// `log_id` is arbitrary and only distinguishes requests.
+    pub fn request_log(
+        channel_id: String,
+        log_id: impl Into<String>,
+        length: usize,
+    ) -> SendingWsMsg {
+        SendingWsMsg::ch(
+            channel_id,
+            "requestLog",
+            json!({
+                "id": log_id.into(),
+                "length": length
+            }),
+        )
+    }
 }
+
+#[cfg(test)]
+mod tests;
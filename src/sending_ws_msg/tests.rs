@@ -0,0 +1,32 @@
+use serde_json::json;
+
+use super::*;
+
+#[test]
+fn serialize() {
+    assert_eq!(
+        serde_json::to_value(SendingWsMsg::unsub_note("9xyz000001".to_owned())).unwrap(),
+        json!({"type": "unsubNote", "body": {"id": "9xyz000001"}})
+    );
+    assert_eq!(
+        serde_json::to_value(SendingWsMsg::disconnect("a".to_owned())).unwrap(),
+        json!({"type": "disconnect", "body": {"id": "a"}})
+    );
+    assert_eq!(
+        serde_json::to_value(SendingWsMsg::ch(
+            "a".to_owned(),
+            "putStone",
+            json!({"pos": 3, "id": "b"})
+        ))
+        .unwrap(),
+        json!({"type": "ch", "body": {"id": "a", "type": "putStone", "body": {"pos": 3, "id": "b"}}})
+    );
+}
+
+#[test]
+fn request_log() {
+    assert_eq!(
+        serde_json::to_value(SendingWsMsg::request_log("a".to_owned(), "log1", 50)).unwrap(),
+        json!({"type": "ch", "body": {"id": "a", "type": "requestLog", "body": {"id": "log1", "length": 50}}})
+    );
+}
@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+
+use crate::receiving_ws_msg::{QueueStats, ServerStats, StatsBody, WsMsgChannelBody};
+
+/// Keeps the most recent `capacity` stats, dropping the oldest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsHistory<T> {
+    capacity: usize,
+    buf: VecDeque<T>,
+}
+
+impl<T> StatsHistory<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            buf: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    pub fn push(&mut self, stats: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.buf.len() == self.capacity {
+            self.buf.pop_front();
+        }
+        self.buf.push_back(stats);
+    }
+
+    /// Replaces the history with a `statsLog`, which is ordered newest first.
+    pub fn load_log(&mut self, log: impl IntoIterator<Item = T>) {
+        let mut log: Vec<_> = log.into_iter().take(self.capacity).collect();
+        log.reverse();
+        self.buf = log.into();
+    }
+
+    /// Oldest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.buf.iter()
+    }
+
+    pub fn latest(&self) -> Option<&T> {
+        self.buf.back()
+    }
+}
+
+/// Collects `stats` and `statsLog` messages of the `serverStats` and `queueStats` channels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsCollector {
+    pub server: StatsHistory<ServerStats>,
+    pub queue: StatsHistory<QueueStats>,
+}
+
+impl StatsCollector {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            server: StatsHistory::new(capacity),
+            queue: StatsHistory::new(capacity),
+        }
+    }
+
+    /// Records `msg` if it is stats. Returns `false` otherwise.
+    pub fn apply(&mut self, msg: &WsMsgChannelBody) -> bool {
+        match msg {
+            WsMsgChannelBody::Stats { body, .. } => {
+                match body {
+                    StatsBody::Server(stats) => self.server.push(*stats),
+                    StatsBody::Queue(stats) => self.queue.push(*stats),
+                }
+                true
+            }
+            WsMsgChannelBody::StatsLog { body, .. } => {
+                let server: Vec<_> = body
+                    .iter()
+                    .filter_map(|x| match x {
+                        StatsBody::Server(stats) => Some(*stats),
+                        StatsBody::Queue(_) => None,
+                    })
+                    .collect();
+                let queue: Vec<_> = body
+                    .iter()
+                    .filter_map(|x| match x {
+                        StatsBody::Queue(stats) => Some(*stats),
+                        StatsBody::Server(_) => None,
+                    })
+                    .collect();
+
+                if !server.is_empty() {
+                    self.server.load_log(server);
+                }
+                if !queue.is_empty() {
+                    self.queue.load_log(queue);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
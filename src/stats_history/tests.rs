@@ -0,0 +1,60 @@
+use super::*;
+use crate::receiving_ws_msg::ReceivingWsMsg;
+
+fn channel_body(json: &str) -> WsMsgChannelBody {
+    let msg: ReceivingWsMsg = serde_json::from_str(json).unwrap();
+    let ReceivingWsMsg::Channel(body) = msg else {
+        panic!("{msg:?}");
+    };
+    body
+}
+
+#[test]
+fn ring_buffer() {
+    let mut history = StatsHistory::new(3);
+    for i in 0..5 {
+        history.push(i);
+    }
+
+    assert_eq!(history.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+    assert_eq!(history.latest(), Some(&4));
+
+    history.load_log([9, 8, 7, 6]);
+    assert_eq!(history.iter().copied().collect::<Vec<_>>(), vec![7, 8, 9]);
+}
+
+#[test]
+fn collect_server_stats() {
+    let mut collector = StatsCollector::new(10);
+
+    assert!(collector.apply(&channel_body(
+        r#"{"type":"channel","body":{"id":"a","type":"statsLog","body":[
+            {"cpu":0.5,"mem":{"used":200,"active":100},"net":{"rx":2,"tx":1},"fs":{"r":0,"w":0}},
+            {"cpu":0.25,"mem":{"used":100,"active":50},"net":{"rx":0,"tx":0},"fs":{"r":0,"w":0}}
+        ]}}"#
+    )));
+    assert!(collector.apply(&channel_body(
+        r#"{"type":"channel","body":{"id":"a","type":"stats","body":{"cpu":0.75,"mem":{"used":300,"active":150},"net":{"rx":0,"tx":0},"fs":{"r":1,"w":1}}}}"#
+    )));
+
+    let cpu: Vec<_> = collector.server.iter().map(|x| x.cpu).collect();
+    assert_eq!(cpu, vec![0.25, 0.5, 0.75]);
+    assert!(collector.queue.is_empty());
+}
+
+#[test]
+fn collect_queue_stats() {
+    let mut collector = StatsCollector::new(10);
+
+    assert!(collector.apply(&channel_body(
+        r#"{"type":"channel","body":{"id":"a","type":"stats","body":{
+            "deliver":{"activeSincePrevTick":3,"active":1,"waiting":5,"delayed":0},
+            "inbox":{"activeSincePrevTick":10,"active":2,"waiting":0,"delayed":1}
+        }}}"#
+    )));
+
+    let latest = collector.queue.latest().unwrap();
+    assert_eq!(latest.deliver.waiting, 5);
+    assert_eq!(latest.inbox.active_since_prev_tick, 10);
+    assert!(collector.server.is_empty());
+}
@@ -1,21 +1,168 @@
+use std::collections::HashSet;
+
 use anyhow::bail;
 use futures::{SinkExt, StreamExt};
+use serde::Serialize;
 use serde_json::json;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 use tracing::{info, warn};
 use uuid::Uuid;
 
-use crate::{receiving_ws_msg, sending_ws_msg};
+use crate::{receiving_ws_msg, sending_ws_msg::SendingWsMsg, Client};
+
+pub(crate) fn stream_url(host: impl AsRef<str>, api_key: Option<impl AsRef<str>>) -> String {
+    match api_key {
+        Some(api_key) => format!("wss://{}/stream?i={}", host.as_ref(), api_key.as_ref()),
+        None => format!("wss://{}/stream", host.as_ref()),
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum MiChannel {
-    Global,
-    Home,
-    Hybrid,
-    Local,
+    Global(TimelineParams),
+    Home(TimelineParams),
+    Hybrid(TimelineParams),
+    Local(TimelineParams),
     Main,
-    Channel { channel_id: String },
-    Antenna { antenna_id: String },
+    Channel(ChannelParams),
+    Antenna(AntennaParams),
+    Hashtag(HashtagParams),
+    UserList(UserListParams),
+    RoleTimeline(RoleTimelineParams),
+    ServerStats,
+    QueueStats,
+
+    /// Requires a moderator.
+    Admin,
+
+    /// Reversi matching and invitations.
+    Reversi,
+    ReversiGame(ReversiGameParams),
+    ChatUser(ChatUserParams),
+    ChatRoom(ChatRoomParams),
+}
+
+impl MiChannel {
+    /// Name of the channel in a `connect` message.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MiChannel::Global(_) => "globalTimeline",
+            MiChannel::Home(_) => "homeTimeline",
+            MiChannel::Hybrid(_) => "hybridTimeline",
+            MiChannel::Local(_) => "localTimeline",
+            MiChannel::Main => "main",
+            MiChannel::Channel(_) => "channel",
+            MiChannel::Antenna(_) => "antenna",
+            MiChannel::Hashtag(_) => "hashtag",
+            MiChannel::UserList(_) => "userList",
+            MiChannel::RoleTimeline(_) => "roleTimeline",
+            MiChannel::ServerStats => "serverStats",
+            MiChannel::QueueStats => "queueStats",
+            MiChannel::Admin => "admin",
+            MiChannel::Reversi => "reversi",
+            MiChannel::ReversiGame(_) => "reversiGame",
+            MiChannel::ChatUser(_) => "chatUser",
+            MiChannel::ChatRoom(_) => "chatRoom",
+        }
+    }
+
+    /// Params of a `connect` message.
+    pub fn params(&self) -> serde_json::Result<serde_json::Value> {
+        match self {
+            MiChannel::Global(params)
+            | MiChannel::Home(params)
+            | MiChannel::Hybrid(params)
+            | MiChannel::Local(params) => serde_json::to_value(params),
+            MiChannel::Channel(params) => serde_json::to_value(params),
+            MiChannel::Antenna(params) => serde_json::to_value(params),
+            MiChannel::Hashtag(params) => serde_json::to_value(params),
+            MiChannel::UserList(params) => serde_json::to_value(params),
+            MiChannel::RoleTimeline(params) => serde_json::to_value(params),
+            MiChannel::ReversiGame(params) => serde_json::to_value(params),
+            MiChannel::ChatUser(params) => serde_json::to_value(params),
+            MiChannel::ChatRoom(params) => serde_json::to_value(params),
+            MiChannel::Main
+            | MiChannel::ServerStats
+            | MiChannel::QueueStats
+            | MiChannel::Admin
+            | MiChannel::Reversi => Ok(json!({})),
+        }
+    }
+}
+
+/// Params of the global, home, hybrid and local timelines. `None` leaves the server default.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_renotes: Option<bool>,
+
+    /// Hybrid and local timelines only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_replies: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_files: Option<bool>,
+
+    /// Sends notes with fewer fields.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimize: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelParams {
+    pub channel_id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AntennaParams {
+    pub antenna_id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashtagParams {
+    /// Tags without `#`, as an OR of ANDs.
+    pub q: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserListParams {
+    pub list_id: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_renotes: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub with_files: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleTimelineParams {
+    pub role_id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReversiGameParams {
+    pub game_id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatUserParams {
+    /// The other user of the direct chat.
+    pub other_id: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatRoomParams {
+    pub room_id: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -31,6 +178,7 @@ pub struct WsConnection {
     ws: tokio_tungstenite::WebSocketStream<
         tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
     >,
+    active_channels: HashSet<String>,
 }
 
 impl WsConnection {
@@ -38,15 +186,21 @@ impl WsConnection {
         host: impl AsRef<str>,
         api_key: Option<impl AsRef<str>>,
     ) -> anyhow::Result<WsConnection> {
-        let req;
-        if let Some(api_key) = api_key {
-            req = format!("wss://{}/stream?i={}", host.as_ref(), api_key.as_ref());
-        } else {
-            req = format!("wss://{}/stream", host.as_ref());
-        }
-        let (ws, _res) = connect_async(req).await?;
+        Self::connect(&stream_url(host, api_key)).await
+    }
 
-        Ok(WsConnection { ws })
+    /// Connects to [`Client::streaming_url`], sharing its base URL and token.
+    pub async fn from_client(client: &Client) -> anyhow::Result<WsConnection> {
+        Self::connect(client.streaming_url()?.as_str()).await
+    }
+
+    pub(crate) async fn connect(url: &str) -> anyhow::Result<WsConnection> {
+        let (ws, _res) = connect_async(url).await?;
+
+        Ok(WsConnection {
+            ws,
+            active_channels: HashSet::new(),
+        })
     }
 
     #[tracing::instrument(skip(self))]
@@ -55,75 +209,72 @@ impl WsConnection {
         channel: MiChannel,
     ) -> anyhow::Result<ChannelConnection> {
         let id = Uuid::new_v4().to_string();
+        self.connect_to_channel_with_id(&channel, id).await
+    }
 
-        let msg = match channel {
-            MiChannel::Global => json!({
-                "type": "connect",
-                "body": {
-                    "id": id.clone(),
-                    "channel": "globalTimeline",
-                    "params": {}
-                }
-            }),
-            MiChannel::Home => json!({
-                "type": "connect",
-                "body": {
-                    "id": id.clone(),
-                    "channel": "homeTimeline",
-                    "params": {}
-                }
-            }),
-            MiChannel::Hybrid => json!({
-                "type": "connect",
-                "body": {
-                    "id": id.clone(),
-                    "channel": "hybridTimeline",
-                    "params": {}
-                }
-            }),
-            MiChannel::Local => json!({
-                "type": "connect",
-                "body": {
-                    "id": id.clone(),
-                    "channel": "localTimeline",
-                    "params": {}
-                }
-            }),
-            MiChannel::Main => json!({
-                "type": "connect",
-                "body": {
-                    "id": id.clone(),
-                    "channel": "main",
-                    "params": {}
-                }
-            }),
-            MiChannel::Channel { channel_id } => json!({
-                "type": "connect",
-                "body": {
-                    "id": id.clone(),
-                    "channel": "channel",
-                    "params": {
-                        "channelId": channel_id
-                    }
-                }
-            }),
-            MiChannel::Antenna { antenna_id } => json!({
-                "type": "connect",
-                "body": {
-                    "id": id.clone(),
-                    "channel": "antenna",
-                    "params": {
-                        "antennaId": antenna_id
-                    }
-                }
-            }),
-        };
+    /// Connects with a known `id`, so that a channel can be restored after reconnecting.
+    pub(crate) async fn connect_to_channel_with_id(
+        &mut self,
+        channel: &MiChannel,
+        id: String,
+    ) -> anyhow::Result<ChannelConnection> {
+        let msg = json!({
+            "type": "connect",
+            "body": {
+                "id": id.clone(),
+                "channel": channel.name(),
+                "params": channel.params()?
+            }
+        });
 
         self.ws.send(Message::text(msg.to_string())).await?;
+        self.active_channels.insert(id.clone());
 
         Ok(ChannelConnection { id })
     }
 
+    /// Ids of channels connected and not yet disconnected.
+    pub fn active_channels(&self) -> impl Iterator<Item = &str> {
+        self.active_channels.iter().map(String::as_str)
+    }
+
+    pub fn is_active(&self, channel: &ChannelConnection) -> bool {
+        self.active_channels.contains(&channel.id)
+    }
+
+    pub async fn disconnect(
+        &mut self,
+        channel: ChannelConnection,
+    ) -> anyhow::Result<Result<(), ConnectionError>> {
+        self.send(SendingWsMsg::disconnect(channel.id)).await
+    }
+
+    /// Sends a channel-scoped `ch` message, e.g. to play reversi.
+    pub async fn send_to_channel(
+        &mut self,
+        channel: &ChannelConnection,
+        r#type: impl Into<String>,
+        body: serde_json::Value,
+    ) -> anyhow::Result<Result<(), ConnectionError>> {
+        self.send(SendingWsMsg::ch(channel.id.clone(), r#type, body))
+            .await
+    }
+
+    /// Starts receiving `noteUpdated` events of the note.
+    pub async fn sub_note(
+        &mut self,
+        note_id: impl Into<String>,
+    ) -> anyhow::Result<Result<(), ConnectionError>> {
+        self.send(SendingWsMsg::sub_note(note_id.into())).await
+    }
+
+    pub async fn unsub_note(
+        &mut self,
+        note_id: impl Into<String>,
+    ) -> anyhow::Result<Result<(), ConnectionError>> {
+        self.send(SendingWsMsg::unsub_note(note_id.into())).await
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn receive(
         &mut self,
@@ -155,10 +306,11 @@ impl WsConnection {
         }
     }
 
-    pub async fn send(
-        &mut self,
-        msg: sending_ws_msg::SendingWsMsg,
-    ) -> anyhow::Result<Result<(), ConnectionError>> {
+    pub async fn send(&mut self, msg: SendingWsMsg) -> anyhow::Result<Result<(), ConnectionError>> {
+        if let SendingWsMsg::Disconnect(body) = &msg {
+            self.active_channels.remove(&body.id);
+        }
+
         let r = self
             .ws
             .send(Message::text(serde_json::to_string(&msg)?))
@@ -186,3 +338,6 @@ impl WsConnection {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,107 @@
+use futures::StreamExt;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio_tungstenite::accept_async;
+
+use super::*;
+
+#[test]
+fn timeline_params() {
+    let channel = MiChannel::Hybrid(TimelineParams {
+        with_renotes: Some(false),
+        with_files: Some(true),
+        ..Default::default()
+    });
+
+    assert_eq!(channel.name(), "hybridTimeline");
+    assert_eq!(
+        channel.params().unwrap(),
+        json!({"withRenotes": false, "withFiles": true})
+    );
+    assert_eq!(
+        MiChannel::Home(Default::default()).params().unwrap(),
+        json!({})
+    );
+}
+
+#[test]
+fn channel_params() {
+    let channel = MiChannel::Hashtag(HashtagParams {
+        q: vec![vec!["misskey".to_owned(), "rust".to_owned()]],
+    });
+    assert_eq!(channel.name(), "hashtag");
+    assert_eq!(
+        channel.params().unwrap(),
+        json!({"q": [["misskey", "rust"]]})
+    );
+
+    let channel = MiChannel::ChatRoom(ChatRoomParams {
+        room_id: "9room00001".to_owned(),
+    });
+    assert_eq!(channel.name(), "chatRoom");
+    assert_eq!(channel.params().unwrap(), json!({"roomId": "9room00001"}));
+
+    assert_eq!(MiChannel::ServerStats.params().unwrap(), json!({}));
+}
+
+#[tokio::test]
+async fn sends_frames() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/stream", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+
+        let mut frames = Vec::new();
+        while let Some(Ok(Message::Text(text))) = ws.next().await {
+            frames.push(serde_json::from_str::<Value>(&text).unwrap());
+        }
+        frames
+    });
+
+    let mut conn = WsConnection::connect(&url).await.unwrap();
+    let home = conn
+        .connect_to_channel(MiChannel::Home(Default::default()))
+        .await
+        .unwrap();
+    let game = conn
+        .connect_to_channel(MiChannel::ReversiGame(ReversiGameParams {
+            game_id: "9game00001".to_owned(),
+        }))
+        .await
+        .unwrap();
+    assert!(conn.is_active(&home));
+    assert_eq!(conn.active_channels().count(), 2);
+
+    conn.send_to_channel(&game, "putStone", json!({"pos": 19, "id": "put1"}))
+        .await
+        .unwrap()
+        .ok()
+        .unwrap();
+    conn.sub_note("9xyz000001").await.unwrap().ok().unwrap();
+    conn.unsub_note("9xyz000001").await.unwrap().ok().unwrap();
+    conn.disconnect(home.clone()).await.unwrap().ok().unwrap();
+
+    assert!(!conn.is_active(&home));
+    assert!(conn.is_active(&game));
+    assert_eq!(
+        conn.active_channels().collect::<Vec<_>>(),
+        [game.id.as_str()]
+    );
+
+    drop(conn);
+    let frames = server.await.unwrap();
+    assert_eq!(
+        frames[2..],
+        [
+            json!({
+                "type": "ch",
+                "body": {"id": game.id, "type": "putStone", "body": {"pos": 19, "id": "put1"}}
+            }),
+            json!({"type": "subNote", "body": {"id": "9xyz000001"}}),
+            json!({"type": "unsubNote", "body": {"id": "9xyz000001"}}),
+            json!({"type": "disconnect", "body": {"id": home.id}}),
+        ]
+    );
+}
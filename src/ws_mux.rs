@@ -0,0 +1,182 @@
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use anyhow::bail;
+use futures::Stream;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    receiving_ws_msg::{ReceivingWsMsg, WsMsgChannelBody},
+    sending_ws_msg::SendingWsMsg,
+    ws_connection::{ConnectionError, MiChannel, WsConnection},
+};
+
+enum Command {
+    Connect {
+        channel: MiChannel,
+        id: String,
+        tx: mpsc::UnboundedSender<WsMsgChannelBody>,
+    },
+    Disconnect {
+        id: String,
+    },
+    Send(SendingWsMsg),
+}
+
+/// Shares one [`WsConnection`] between per-channel [`ChannelStream`]s.
+///
+/// A background task owns the socket and routes each channel message to the stream of its
+/// channel. It runs until the socket closes or this and every stream are dropped.
+pub struct WsMux {
+    cmd: mpsc::UnboundedSender<Command>,
+    rest: mpsc::UnboundedReceiver<ReceivingWsMsg>,
+}
+
+impl WsMux {
+    /// Spawns the background task on the current tokio runtime.
+    pub fn new(ws: WsConnection) -> WsMux {
+        let (cmd, cmd_rx) = mpsc::unbounded_channel();
+        let (rest_tx, rest) = mpsc::unbounded_channel();
+        tokio::spawn(run(ws, cmd_rx, rest_tx));
+
+        WsMux { cmd, rest }
+    }
+
+    pub fn connect_to_channel(&self, channel: MiChannel) -> anyhow::Result<ChannelStream> {
+        let id = Uuid::new_v4().to_string();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let cmd = Command::Connect {
+            channel,
+            id: id.clone(),
+            tx,
+        };
+        if self.cmd.send(cmd).is_err() {
+            bail!("connection closed");
+        }
+
+        Ok(ChannelStream {
+            id,
+            rx,
+            cmd: self.cmd.clone(),
+        })
+    }
+
+    pub fn send(&self, msg: SendingWsMsg) -> anyhow::Result<()> {
+        if self.cmd.send(Command::Send(msg)).is_err() {
+            bail!("connection closed");
+        }
+        Ok(())
+    }
+
+    /// Receives messages not addressed to a channel, such as `noteUpdated`.
+    ///
+    /// Returns `None` once the connection is closed.
+    pub async fn receive(&mut self) -> Option<ReceivingWsMsg> {
+        self.rest.recv().await
+    }
+}
+
+/// Messages of one channel. Dropping it disconnects from the channel.
+pub struct ChannelStream {
+    id: String,
+    rx: mpsc::UnboundedReceiver<WsMsgChannelBody>,
+    cmd: mpsc::UnboundedSender<Command>,
+}
+
+impl ChannelStream {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl Stream for ChannelStream {
+    type Item = WsMsgChannelBody;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Drop for ChannelStream {
+    fn drop(&mut self) {
+        let id = std::mem::take(&mut self.id);
+        _ = self.cmd.send(Command::Disconnect { id });
+    }
+}
+
+async fn run(
+    mut ws: WsConnection,
+    mut cmd: mpsc::UnboundedReceiver<Command>,
+    rest: mpsc::UnboundedSender<ReceivingWsMsg>,
+) {
+    let mut channels = HashMap::new();
+
+    loop {
+        tokio::select! {
+            cmd = cmd.recv() => match cmd {
+                Some(Command::Connect { channel, id, tx }) => {
+                    if let Err(err) = ws.connect_to_channel_with_id(&channel, id.clone()).await {
+                        warn!("connection error: {err:?}");
+                        return;
+                    }
+                    channels.insert(id, tx);
+                }
+                Some(Command::Disconnect { id }) => {
+                    if channels.remove(&id).is_some()
+                        && !send(&mut ws, SendingWsMsg::disconnect(id)).await
+                    {
+                        return;
+                    }
+                }
+                Some(Command::Send(msg)) => {
+                    if !send(&mut ws, msg).await {
+                        return;
+                    }
+                }
+                None => {
+                    info!("all handles dropped, closing");
+                    return;
+                }
+            },
+            msg = ws.receive() => match msg {
+                Ok(Ok(ReceivingWsMsg::Channel(body))) => match channels.get(body.id()) {
+                    Some(tx) => _ = tx.send(body),
+                    None => debug!("message to unknown channel: {body:?}"),
+                },
+                Ok(Ok(msg)) => _ = rest.send(msg),
+                Ok(Err(ConnectionError::Disconnected)) => {
+                    info!("disconnected");
+                    return;
+                }
+                Err(err) => {
+                    warn!("connection error: {err:?}");
+                    return;
+                }
+            },
+        }
+    }
+}
+
+/// Returns `false` if the connection is gone.
+async fn send(ws: &mut WsConnection, msg: SendingWsMsg) -> bool {
+    match ws.send(msg).await {
+        Ok(Ok(())) => true,
+        Ok(Err(ConnectionError::Disconnected)) => {
+            info!("disconnected");
+            false
+        }
+        Err(err) => {
+            warn!("connection error: {err:?}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,65 @@
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+use super::*;
+use crate::fixtures;
+
+fn note_msg(channel_id: &str, note_id: &str) -> Message {
+    let msg = json!({
+        "type": "channel",
+        "body": {
+            "id": channel_id,
+            "type": "note",
+            "body": fixtures::note(json!({"id": note_id}))
+        }
+    });
+    Message::text(msg.to_string())
+}
+
+fn text_json(msg: Message) -> Value {
+    serde_json::from_str(msg.to_text().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn routes_by_channel_and_disconnects_on_drop() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/stream", listener.local_addr().unwrap());
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+
+        let home = text_json(ws.next().await.unwrap().unwrap());
+        let local = text_json(ws.next().await.unwrap().unwrap());
+        assert_eq!(home["body"]["channel"], "homeTimeline");
+        assert_eq!(local["body"]["channel"], "localTimeline");
+        let home_id = home["body"]["id"].as_str().unwrap().to_owned();
+        let local_id = local["body"]["id"].as_str().unwrap().to_owned();
+
+        ws.send(note_msg(&local_id, "note-local")).await.unwrap();
+        ws.send(note_msg(&home_id, "note-home")).await.unwrap();
+
+        let disconnect = text_json(ws.next().await.unwrap().unwrap());
+        assert_eq!(disconnect["type"], "disconnect");
+        assert_eq!(disconnect["body"]["id"], local_id.as_str());
+    });
+
+    let mux = WsMux::new(WsConnection::connect(&url).await.unwrap());
+    let mut home = mux
+        .connect_to_channel(MiChannel::Home(Default::default()))
+        .unwrap();
+    let local = mux
+        .connect_to_channel(MiChannel::Local(Default::default()))
+        .unwrap();
+
+    let Some(WsMsgChannelBody::Note { id, body }) = home.next().await else {
+        panic!();
+    };
+    assert_eq!(id, home.id());
+    assert_eq!(body.id, "note-home");
+
+    drop(local);
+    server.await.unwrap();
+}